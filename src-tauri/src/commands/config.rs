@@ -52,6 +52,92 @@ pub fn set_bookmarks_enabled(enabled: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// Replaces the synonym groups; blank words and groups with fewer than two words are dropped
+pub fn set_synonyms(groups: Vec<Vec<String>>) -> Result<(), String> {
+    let groups = groups
+        .into_iter()
+        .map(|group| {
+            group
+                .into_iter()
+                .map(|word| word.trim().to_string())
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|group| group.len() > 1)
+        .collect();
+
+    let mut config = CONFIG.lock().map_err(|e| e.to_string())?;
+    config.synonyms = groups;
+    save_config(&config)?;
+    Ok(())
+}
+
+pub fn set_editor_command(command: Option<String>) -> Result<(), String> {
+    let mut config = CONFIG.lock().map_err(|e| e.to_string())?;
+    config.editor_command = command.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    save_config(&config)?;
+    Ok(())
+}
+
+/// Opens a file at a line with the configured editor command
+///
+/// `{path}` and `{line}` in the command are replaced; without `{path}` the path is
+/// appended. Returns false if no editor is configured.
+pub fn open_in_editor(path: &str, line: u64) -> Result<bool, String> {
+    let Some(command) = get_config()?.editor_command else {
+        return Ok(false);
+    };
+
+    let mut args = split_command_line(&command);
+    if args.is_empty() {
+        return Ok(false);
+    }
+    if !args.iter().any(|arg| arg.contains("{path}")) {
+        args.push("{path}".to_string());
+    }
+    let args: Vec<String> = args
+        .iter()
+        .map(|arg| arg.replace("{path}", path).replace("{line}", &line.to_string()))
+        .collect();
+
+    std::process::Command::new(&args[0])
+        .args(&args[1..])
+        .spawn()
+        .map_err(|e| format!("Failed to run editor '{}': {}", args[0], e))?;
+    Ok(true)
+}
+
+/// Splits a command line on whitespace, keeping double-quoted parts together
+/// (e.g. `"C:\Program Files\Editor\editor.exe" -g {path}:{line}`)
+fn split_command_line(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+    for c in command.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        args.push(current);
+    }
+    args
+}
+
 pub fn get_browser_status() -> Result<crate::services::browser_extractor::BrowserStatus, String> {
     let installed = browser_extractor::get_installed_browsers();
     Ok(crate::services::browser_extractor::BrowserStatus {
@@ -1,8 +1,14 @@
+use crate::commands::jobs;
+use crate::commands::{IndexJob, IndexJobKind, SharedIndex};
 use crate::services::tantivy_engine;
 use crate::services::file_watcher;
 use std::path::Path;
 
-pub fn add_indexed_folder(path: String) -> Result<(), String> {
+/// Adds a folder to the config and queues a background job to index it; returns the job ID
+pub fn add_indexed_folder<F>(path: String, emit: F) -> Result<u64, String>
+where
+    F: Fn(IndexJob) + Send + 'static,
+{
     let path = Path::new(&path);
     if !path.exists() || !path.is_dir() {
         return Err("Invalid folder path".to_string());
@@ -17,13 +23,11 @@ pub fn add_indexed_folder(path: String) -> Result<(), String> {
     }
     drop(config);
 
-    // Index the folder
-    tantivy_engine::index_folder(&path_str).map_err(|e| e.to_string())?;
-    
     // Add to file watcher for real-time updates
     let _ = file_watcher::add_watch_folder(&path_str);
-    
-    Ok(())
+
+    // Index the folder in the background
+    jobs::start_index_job(IndexJobKind::AddFolder, vec![path_str], emit)
 }
 
 pub fn remove_indexed_folder(path: String) -> Result<(), String> {
@@ -41,36 +45,170 @@ pub fn remove_indexed_folder(path: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Replaces the active workspace's excluded folders; returns how many indexed files were dropped
+///
+/// Entries are folder names (e.g. `node_modules`) or full paths.
+pub fn set_excluded_folders(folders: Vec<String>) -> Result<u32, String> {
+    let folders: Vec<String> = folders
+        .iter()
+        .map(|folder| folder.trim().to_string())
+        .filter(|folder| !folder.is_empty())
+        .collect();
+
+    let mut config = crate::commands::config::CONFIG.lock().map_err(|e| e.to_string())?;
+    config.excluded_folders = folders.clone();
+    crate::commands::config::save_config(&config)?;
+    drop(config);
+
+    tantivy_engine::set_exclusions(folders).map_err(|e| e.to_string())
+}
+
+/// Opens the configured shared indexes (called on startup)
+///
+/// Shared indexes that can't be opened (e.g. an unmounted network drive) are skipped.
+pub fn open_shared_indexes() -> Result<(), String> {
+    let config = crate::commands::config::get_config()?;
+    for shared in &config.shared_indexes {
+        if let Err(e) = tantivy_engine::open_shared_index(&shared.label, Path::new(&shared.path)) {
+            eprintln!("Failed to open shared index '{}': {}", shared.label, e);
+        }
+    }
+    Ok(())
+}
+
+pub fn list_shared_indexes() -> Result<Vec<SharedIndex>, String> {
+    Ok(crate::commands::config::get_config()?.shared_indexes)
+}
+
+/// Mounts a prebuilt index read-only and searches it together with the personal index
+pub fn add_shared_index(label: String, path: String) -> Result<(), String> {
+    let label = label.trim().to_string();
+    if label.is_empty() {
+        return Err("Shared indexes need a label".to_string());
+    }
+
+    let mut config = crate::commands::config::CONFIG.lock().map_err(|e| e.to_string())?;
+    if config.shared_indexes.iter().any(|shared| shared.label == label) {
+        return Err(format!("A shared index named '{}' already exists", label));
+    }
+    // Opening validates the directory and schema before anything is saved
+    tantivy_engine::open_shared_index(&label, Path::new(&path)).map_err(|e| e.to_string())?;
+    config.shared_indexes.push(SharedIndex { label, path });
+    crate::commands::config::save_config(&config)
+}
+
+pub fn remove_shared_index(label: String) -> Result<(), String> {
+    let mut config = crate::commands::config::CONFIG.lock().map_err(|e| e.to_string())?;
+    config.shared_indexes.retain(|shared| shared.label != label);
+    crate::commands::config::save_config(&config)?;
+    drop(config);
+
+    tantivy_engine::close_shared_index(&label).map_err(|e| e.to_string())
+}
+
 pub fn get_indexed_folders() -> Result<Vec<String>, String> {
     let config = crate::commands::config::CONFIG.lock().map_err(|e| e.to_string())?;
     Ok(config.indexed_folders.clone())
 }
 
-pub fn rebuild_index() -> Result<(), String> {
+/// Queues a background job that rebuilds the index from all folders and browser data; returns the job ID
+pub fn rebuild_index<F>(emit: F) -> Result<u64, String>
+where
+    F: Fn(IndexJob) + Send + 'static,
+{
     let config = crate::commands::config::CONFIG.lock().map_err(|e| e.to_string())?;
     let folders = config.indexed_folders.clone();
-    let enable_history = config.enable_history;
-    let enable_bookmarks = config.enable_bookmarks;
     drop(config);
 
-    tantivy_engine::rebuild_index(&folders).map_err(|e| e.to_string())?;
-
-    if enable_history || enable_bookmarks {
-        let browser_data = crate::services::browser_extractor::extract_all_browser_data(enable_history, enable_bookmarks);
-        if !browser_data.is_empty() {
-             tantivy_engine::index_browser_data(browser_data).map_err(|e| e.to_string())?;
-        }
-    }
-
-    Ok(())
+    jobs::start_index_job(IndexJobKind::Reindex, folders, emit)
 }
 
 pub fn get_document_count() -> Result<u64, String> {
     tantivy_engine::get_document_count().map_err(|e| e.to_string())
 }
 
+/// Index statistics, including per-folder counts, the last crawl and the watcher state
 pub fn get_index_stats() -> Result<tantivy_engine::IndexStats, String> {
-    tantivy_engine::get_index_stats().map_err(|e| e.to_string())
+    let folders = get_indexed_folders()?;
+    let mut stats = tantivy_engine::get_index_stats(&folders).map_err(|e| e.to_string())?;
+    stats.watcher_running = file_watcher::is_running();
+    stats.watched_folders = file_watcher::watched_folders()?;
+    Ok(stats)
+}
+
+/// Exports the index and its manifest, with the recent queries, into a snapshot archive at `path`
+pub fn export_snapshot(path: String) -> Result<tantivy_engine::SnapshotManifest, String> {
+    let config = crate::commands::config::get_config()?;
+    tantivy_engine::export_snapshot(Path::new(&path), &config.indexed_folders, &config.recent_queries).map_err(|e| e.to_string())
+}
+
+/// Imports a snapshot archive, optionally moving its paths from `rebase_from` to `rebase_to`
+///
+/// Running and queued index jobs are cancelled first, as they write to the index (or the
+/// shadow index) the snapshot replaces. The snapshot's folders that exist on this machine
+/// are added to the config, along with its recent queries, then a background job
+/// reconciles the index with the local files; returns the job ID.
+pub fn import_snapshot<F>(path: String, rebase_from: Option<String>, rebase_to: Option<String>, emit: F) -> Result<u64, String>
+where
+    F: Fn(IndexJob) + Send + 'static,
+{
+    let rebase_from = rebase_from.filter(|p| !p.trim().is_empty());
+    let rebase_to = rebase_to.filter(|p| !p.trim().is_empty());
+    let rebase = match (rebase_from.as_deref(), rebase_to.as_deref()) {
+        (Some(from), Some(to)) => Some((from, to)),
+        (None, None) => None,
+        _ => return Err("Both the old and the new root are needed to rebase paths".to_string()),
+    };
+
+    let manifest = jobs::cancel_all_index_jobs(|| tantivy_engine::import_snapshot(Path::new(&path), rebase))?
+        .map_err(|e| e.to_string())?;
+
+    let mut config = crate::commands::config::CONFIG.lock().map_err(|e| e.to_string())?;
+    let new_folders: Vec<String> = manifest
+        .roots
+        .into_iter()
+        .filter(|root| Path::new(root).is_dir() && !config.indexed_folders.contains(root))
+        .collect();
+    config.indexed_folders.extend(new_folders.iter().cloned());
+    // Imported queries are older than this machine's own, so they go first
+    let mut recent_queries: Vec<String> = manifest
+        .recent_queries
+        .into_iter()
+        .filter(|query| !config.recent_queries.contains(query))
+        .collect();
+    recent_queries.append(&mut config.recent_queries);
+    let excess = recent_queries.len().saturating_sub(crate::commands::search::MAX_RECENT_QUERIES);
+    config.recent_queries = recent_queries.split_off(excess);
+    crate::commands::config::save_config(&config)?;
+    let folders = config.indexed_folders.clone();
+    drop(config);
+
+    for folder in &new_folders {
+        let _ = file_watcher::add_watch_folder(folder);
+    }
+
+    jobs::start_index_job(IndexJobKind::Import, folders, emit)
+}
+
+/// Compacts the index into a single segment
+pub fn merge_index_segments() -> Result<tantivy_engine::MaintenanceResult, String> {
+    tantivy_engine::merge_segments().map_err(|e| e.to_string())
+}
+
+/// Deletes index files that no segment uses anymore
+pub fn garbage_collect_index() -> Result<tantivy_engine::MaintenanceResult, String> {
+    tantivy_engine::garbage_collect().map_err(|e| e.to_string())
+}
+
+/// Checks index health against the configured folders
+pub fn verify_index() -> Result<tantivy_engine::IndexVerification, String> {
+    let folders = get_indexed_folders()?;
+    tantivy_engine::verify_index(&folders).map_err(|e| e.to_string())
+}
+
+/// Groups of indexed files with identical content, from the hashes stored at index time
+pub fn find_duplicate_files() -> Result<Vec<tantivy_engine::DuplicateGroup>, String> {
+    tantivy_engine::find_duplicates().map_err(|e| e.to_string())
 }
 
 pub fn get_supported_extensions() -> tantivy_engine::SupportedExtensions {
@@ -0,0 +1,302 @@
+use crate::commands::{IndexJob, IndexJobKind, IndexJobState};
+use crate::services::{browser_extractor, tantivy_engine};
+use crate::services::tantivy_engine::TantivyEngine;
+use once_cell::sync::Lazy;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Event name for index job progress updates
+pub const INDEX_PROGRESS_EVENT: &str = "index-progress";
+
+/// Files indexed per batch; the engine is released between batches
+const JOB_BATCH_SIZE: usize = 64;
+/// Number of finished jobs kept around for `list_index_jobs`
+const MAX_FINISHED_JOBS: usize = 20;
+/// How often a paused job checks whether it was resumed or cancelled
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Pause/cancel flags shared between the commands and the job thread
+#[derive(Default)]
+struct JobControl {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+struct JobEntry {
+    job: IndexJob,
+    control: Arc<JobControl>,
+}
+
+static JOBS: Lazy<Mutex<Vec<JobEntry>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Held by the running job, so jobs queue up and index one at a time
+static JOB_RUNNER: Mutex<()> = Mutex::new(());
+
+/// Queues a background index job and returns its ID
+///
+/// `emit` receives a snapshot of the job whenever its state or progress changes.
+pub fn start_index_job<F>(kind: IndexJobKind, folders: Vec<String>, emit: F) -> Result<u64, String>
+where
+    F: Fn(IndexJob) + Send + 'static,
+{
+    let id = NEXT_JOB_ID.fetch_add(1, Ordering::SeqCst);
+    let control = Arc::new(JobControl::default());
+    let job = IndexJob {
+        id,
+        kind,
+        folders,
+        state: IndexJobState::Queued,
+        files_total: 0,
+        files_seen: 0,
+        files_added: 0,
+        files_updated: 0,
+        files_removed: 0,
+        current_path: None,
+        error: None,
+    };
+
+    let mut jobs = JOBS.lock().map_err(|e| e.to_string())?;
+    prune_finished_jobs(&mut jobs);
+    jobs.push(JobEntry { job: job.clone(), control: control.clone() });
+    drop(jobs);
+
+    emit(job.clone());
+    thread::spawn(move || {
+        // Poisoning only means an earlier job panicked; the next one can still run
+        let _runner = JOB_RUNNER.lock().unwrap_or_else(|e| e.into_inner());
+        run_index_job(job, &control, &emit);
+    });
+
+    Ok(id)
+}
+
+/// Lists queued, running and recently finished jobs, oldest first
+pub fn list_index_jobs() -> Result<Vec<IndexJob>, String> {
+    let jobs = JOBS.lock().map_err(|e| e.to_string())?;
+    Ok(jobs.iter().map(|entry| entry.job.clone()).collect())
+}
+
+/// Pauses a job after its current batch
+pub fn pause_index_job(job_id: u64) -> Result<(), String> {
+    with_unfinished_job(job_id, |control| control.paused.store(true, Ordering::SeqCst))
+}
+
+/// Resumes a paused job
+pub fn resume_index_job(job_id: u64) -> Result<(), String> {
+    with_unfinished_job(job_id, |control| control.paused.store(false, Ordering::SeqCst))
+}
+
+/// Cancels a job after its current batch
+///
+/// Files an incremental job already indexed stay in the index; a cancelled rebuild
+/// is discarded and the previous index stays live.
+pub fn cancel_index_job(job_id: u64) -> Result<(), String> {
+    with_unfinished_job(job_id, |control| control.cancelled.store(true, Ordering::SeqCst))
+}
+
+/// Cancels every unfinished job and runs `action` once the running one has stopped
+///
+/// Used when switching workspaces, so no job keeps writing into the old index.
+/// Queued jobs still get their turn afterwards, but only to report themselves cancelled.
+pub fn cancel_all_index_jobs<T>(action: impl FnOnce() -> T) -> Result<T, String> {
+    let jobs = JOBS.lock().map_err(|e| e.to_string())?;
+    for entry in jobs.iter().filter(|entry| !entry.job.state.is_finished()) {
+        entry.control.cancelled.store(true, Ordering::SeqCst);
+    }
+    drop(jobs);
+
+    let _runner = JOB_RUNNER.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(action())
+}
+
+fn with_unfinished_job(job_id: u64, action: impl FnOnce(&JobControl)) -> Result<(), String> {
+    let jobs = JOBS.lock().map_err(|e| e.to_string())?;
+    let entry = jobs
+        .iter()
+        .find(|entry| entry.job.id == job_id)
+        .ok_or_else(|| format!("Index job {} not found", job_id))?;
+    if entry.job.state.is_finished() {
+        return Err(format!("Index job {} has already finished", job_id));
+    }
+    action(&entry.control);
+    Ok(())
+}
+
+/// Drops the oldest finished jobs beyond `MAX_FINISHED_JOBS`
+fn prune_finished_jobs(jobs: &mut Vec<JobEntry>) {
+    let finished = jobs.iter().filter(|entry| entry.job.state.is_finished()).count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+    jobs.retain(|entry| {
+        if excess > 0 && entry.job.state.is_finished() {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+/// Runs a job to completion, cancellation or failure
+fn run_index_job(mut job: IndexJob, control: &JobControl, emit: &dyn Fn(IndexJob)) {
+    let mut update = |job: &IndexJob| {
+        if let Ok(mut jobs) = JOBS.lock() {
+            if let Some(entry) = jobs.iter_mut().find(|entry| entry.job.id == job.id) {
+                entry.job = job.clone();
+            }
+        }
+        emit(job.clone());
+    };
+
+    let outcome = index_job_files(&mut job, control, &mut update);
+    job.state = match outcome {
+        Ok(true) => {
+            // Only a stale timestamp is lost if this can't be written
+            let _ = tantivy_engine::record_crawl(true);
+            IndexJobState::Completed
+        }
+        Ok(false) => IndexJobState::Cancelled,
+        Err(e) => {
+            job.error = Some(e);
+            IndexJobState::Failed
+        }
+    };
+    job.current_path = None;
+    update(&job);
+}
+
+/// Does the work of a job; returns `Ok(false)` if it was cancelled
+fn index_job_files(job: &mut IndexJob, control: &JobControl, update: &mut dyn FnMut(&IndexJob)) -> Result<bool, String> {
+    if !wait_while_paused(job, control, update) {
+        return Ok(false);
+    }
+    if job.kind != IndexJobKind::Reindex {
+        return crawl_job_folders(job, control, update, None);
+    }
+
+    // A rebuild is written to a shadow index and only swapped in once it completed,
+    // so the live index keeps serving searches and survives a failed or cancelled rebuild
+    let shadow = tantivy_engine::create_shadow_engine().map_err(|e| e.to_string())?;
+    let outcome = crawl_job_folders(job, control, update, Some(&shadow));
+    let swapped = match outcome {
+        Ok(true) => tantivy_engine::swap_in_shadow(&shadow).map_err(|e| e.to_string()),
+        _ => Ok(()),
+    };
+    if outcome != Ok(true) || swapped.is_err() {
+        let _ = shadow.clear_index();
+    }
+    swapped?;
+    outcome
+}
+
+/// Walks the job's folders and indexes their files in batches, into `shadow` if given
+fn crawl_job_folders(
+    job: &mut IndexJob,
+    control: &JobControl,
+    update: &mut dyn FnMut(&IndexJob),
+    shadow: Option<&TantivyEngine>,
+) -> Result<bool, String> {
+    job.state = IndexJobState::Scanning;
+    update(job);
+    let _ = tantivy_engine::record_crawl(false);
+    let exclusions = crate::commands::config::get_config()?.excluded_folders;
+    let mut files = Vec::new();
+    for folder in &job.folders {
+        if control.cancelled.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        files.extend(tantivy_engine::collect_indexable_files(folder, &exclusions));
+    }
+
+    job.state = IndexJobState::Running;
+    job.files_total = files.len() as u64;
+    update(job);
+
+    // The batches share one writer, so whatever was indexed is committed however the loop ends
+    let indexed = index_job_batches(job, control, update, shadow, &files);
+    let committed = match shadow {
+        Some(shadow) => shadow.commit_job_writes(),
+        None => tantivy_engine::commit_job_writes(),
+    };
+    if !indexed? {
+        return Ok(false);
+    }
+    committed.map_err(|e| e.to_string())?;
+
+    // Reconcile with the disk: drop entries for files deleted while the app was closed
+    // (or, for an imported snapshot, files that don't exist on this machine)
+    if matches!(job.kind, IndexJobKind::StartupScan | IndexJobKind::Import) {
+        if !wait_while_paused(job, control, update) {
+            return Ok(false);
+        }
+        job.files_removed = tantivy_engine::purge_missing_files(&job.folders).map_err(|e| e.to_string())? as u64;
+        job.current_path = None;
+        update(job);
+    }
+
+    // A rebuild starts from an empty index, so browser data has to go into it as well
+    if let Some(shadow) = shadow {
+        let config = crate::commands::config::get_config()?;
+        if config.enable_history || config.enable_bookmarks {
+            let browser_data = browser_extractor::extract_all_browser_data(config.enable_history, config.enable_bookmarks);
+            if !browser_data.is_empty() {
+                shadow.index_browser_data(browser_data).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+/// Indexes the job's files in batches; returns `Ok(false)` if it was cancelled
+fn index_job_batches(
+    job: &mut IndexJob,
+    control: &JobControl,
+    update: &mut dyn FnMut(&IndexJob),
+    shadow: Option<&TantivyEngine>,
+    files: &[PathBuf],
+) -> Result<bool, String> {
+    for batch in files.chunks(JOB_BATCH_SIZE) {
+        // Make what was indexed so far searchable for the length of the pause
+        if control.paused.load(Ordering::SeqCst) {
+            let committed = match shadow {
+                Some(shadow) => shadow.commit_job_writes(),
+                None => tantivy_engine::commit_job_writes(),
+            };
+            committed.map_err(|e| e.to_string())?;
+        }
+        if !wait_while_paused(job, control, update) {
+            return Ok(false);
+        }
+        let indexed = match shadow {
+            Some(shadow) => shadow.index_files(batch),
+            None => tantivy_engine::index_files(batch),
+        };
+        let changes = indexed.map_err(|e| e.to_string())?;
+        job.files_added += changes.added as u64;
+        job.files_updated += changes.updated as u64;
+        job.files_seen += batch.len() as u64;
+        job.current_path = batch.last().map(|path| path.to_string_lossy().to_string());
+        update(job);
+    }
+    Ok(true)
+}
+
+/// Blocks while the job is paused; returns false if it was cancelled
+fn wait_while_paused(job: &mut IndexJob, control: &JobControl, update: &mut dyn FnMut(&IndexJob)) -> bool {
+    let resume_state = job.state;
+    while control.paused.load(Ordering::SeqCst) && !control.cancelled.load(Ordering::SeqCst) {
+        if job.state != IndexJobState::Paused {
+            job.state = IndexJobState::Paused;
+            update(job);
+        }
+        thread::sleep(PAUSE_POLL_INTERVAL);
+    }
+    if job.state == IndexJobState::Paused && !control.cancelled.load(Ordering::SeqCst) {
+        job.state = resume_state;
+        update(job);
+    }
+    !control.cancelled.load(Ordering::SeqCst)
+}
@@ -1,6 +1,8 @@
 pub mod index;
 pub mod search;
 pub mod config;
+pub mod jobs;
+pub mod workspace;
 
 use serde::{Deserialize, Serialize};
 
@@ -10,8 +12,155 @@ pub struct SearchResult {
     pub file_name: String,
     pub score: f32,
     pub record_type: String, // "file", "history", "bookmark"
+    pub url: Option<String>,
+    pub extension: String, // Empty for history/bookmarks
+    pub source: Option<String>, // Browser profile for history/bookmarks, e.g. "Chrome (Default)"
+    pub size: u64,
+    pub modified_time: i64,
+    pub match_positions: Vec<usize>, // Char indices in file_name matched by the query
+    pub workspace: Option<String>, // Set when searching across workspaces (ws:)
+    pub shared_index: Option<String>, // Label of the shared index the result came from
+    pub line: Option<u64>, // 1-based line of a symbol's definition
+    pub symbol_kind: Option<String>, // "function", "struct", "class"... for symbols
+    pub line_matches: Vec<LineMatch>, // Matching lines of text files, for content search hits
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LineMatch {
+    pub line: u64, // 1-based
+    pub text: String, // The line, or an excerpt around the match for long lines
+    pub match_positions: Vec<usize>, // Char indices in text matched by the query
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SearchPage {
+    pub results: Vec<SearchResult>,
+    pub next_cursor: Option<String>, // Pass back to fetch the following page
+    pub total: usize,
+    pub suggestions: Vec<String>, // "Did you mean" queries, only when nothing was found
+    pub facets: SearchFacets, // Counted over all ranked results, not just this page
+    pub error: Option<String>, // Set when a re: scan timed out, so the results are incomplete
+}
+
+/// Hits sharing one value, offered as a one-click refinement of the search
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FacetCount {
+    pub value: String, // "file", "pdf", "doc", or a folder path
+    pub count: usize,
+    pub filter: Option<String>, // Query operator narrowing to this value, e.g. "ext:pdf"
+}
+
+/// Hit counts by record type, extension, type: category and folder
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SearchFacets {
+    pub record_types: Vec<FacetCount>,
+    pub extensions: Vec<FacetCount>, // Most common first, cut to the top few
+    pub types: Vec<FacetCount>,
+    pub folders: Vec<FacetCount>, // Parent folders of file and symbol hits, most common first
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SearchEvent {
+    pub search_id: u64,
+    pub phase: String, // "filename", "content", or "regex" for a re: search
+    pub results: Vec<SearchResult>,
+    pub done: bool, // No further events for this search
+    pub error: Option<String>,
+    pub suggestions: Vec<String>, // "Did you mean" queries, on the last event if nothing was found
+    pub facets: Option<SearchFacets>, // On the last event, counted over the session's hits
+}
+
+/// Where a query completion came from
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionSource {
+    RecentQuery,
+    Filename,
+    Content,
+}
+
+/// A completion of the query being typed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct QuerySuggestion {
+    pub text: String, // The whole query with its last word completed
+    pub source: SuggestionSource,
+}
+
+/// What a background index job does
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexJobKind {
+    /// Index a newly added folder
+    AddFolder,
+    /// Clear the index and rebuild it from all folders (plus browser data)
+    Reindex,
+    /// Catch up on changes made while the app wasn't running, including deleted files
+    StartupScan,
+    /// Reconcile an imported snapshot with the local files, including deleted ones
+    Import,
+}
+
+/// Lifecycle of a background index job
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexJobState {
+    Queued,
+    Scanning, // Walking folders to find files
+    Running,
+    Paused,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+impl IndexJobState {
+    /// Whether the job has stopped for good
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Cancelled | Self::Failed)
+    }
+}
+
+/// Snapshot of a background index job, emitted on every progress update
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IndexJob {
+    pub id: u64,
+    pub kind: IndexJobKind,
+    pub folders: Vec<String>,
+    pub state: IndexJobState,
+    pub files_total: u64, // Known once scanning finishes
+    pub files_seen: u64,
+    pub files_added: u64,
+    pub files_updated: u64,
+    pub files_removed: u64, // Entries purged because the file is gone from disk
+    pub current_path: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Folders and settings of one named workspace, each with its own index
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Workspace {
+    pub name: String,
+    pub indexed_folders: Vec<String>,
+    #[serde(default)]
+    pub excluded_folders: Vec<String>,
+    #[serde(default)]
+    pub enable_history: bool,
+    #[serde(default)]
+    pub enable_bookmarks: bool,
+}
+
+/// A read-only index published by someone else (e.g. of a team documentation share)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SharedIndex {
+    pub label: String,
+    pub path: String,
+}
+
+/// App configuration
+///
+/// The folder, exclusion and browser fields belong to the active workspace, so code
+/// that only cares about the current index reads them directly. Switching workspaces
+/// swaps them with an entry in `workspaces`.
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Config {
     pub indexed_folders: Vec<String>,
@@ -20,9 +169,31 @@ pub struct Config {
     pub enable_history: bool,
     #[serde(default)]
     pub enable_bookmarks: bool,
+    /// Folder names (e.g. `node_modules`) or full paths that are never indexed
+    #[serde(default)]
+    pub excluded_folders: Vec<String>,
+    #[serde(default = "default_workspace_name")]
+    pub active_workspace: String,
+    /// Settings of the inactive workspaces
+    #[serde(default)]
+    pub workspaces: Vec<Workspace>,
+    /// Shared indexes searched alongside the active workspace
+    #[serde(default)]
+    pub shared_indexes: Vec<SharedIndex>,
+    /// Groups of interchangeable query words (e.g. `["k8s", "kubernetes"]`), shared by all workspaces
+    #[serde(default)]
+    pub synonyms: Vec<Vec<String>>,
+    /// Command that opens a file at a line, e.g. `code --goto {path}:{line}`
+    #[serde(default)]
+    pub editor_command: Option<String>,
+    /// The user's recent queries for completion, newest last, shared by all workspaces
+    #[serde(default)]
+    pub recent_queries: Vec<String>,
 }
 
-
+fn default_workspace_name() -> String {
+    crate::services::tantivy_engine::DEFAULT_WORKSPACE.to_string()
+}
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct HotkeyConfig {
@@ -40,6 +211,13 @@ impl Default for Config {
             },
             enable_history: false,
             enable_bookmarks: false,
+            excluded_folders: Vec::new(),
+            active_workspace: default_workspace_name(),
+            workspaces: Vec::new(),
+            shared_indexes: Vec::new(),
+            synonyms: Vec::new(),
+            editor_command: None,
+            recent_queries: Vec::new(),
         }
     }
 }
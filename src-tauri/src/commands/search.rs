@@ -1,14 +1,447 @@
-use crate::commands::SearchResult;
-use crate::services::tantivy_engine;
+use crate::commands::workspace;
+use crate::commands::{QuerySuggestion, SearchEvent, SearchFacets, SearchPage, SearchResult, SuggestionSource};
+use crate::services::tantivy_engine::{self, FacetCounter, SearchFilters};
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Event name for streamed search session results
+pub const SEARCH_RESULTS_EVENT: &str = "search-results";
+
+/// Maximum number of ranked results kept per search for paging
+const MAX_PAGED_RESULTS: usize = 5_000;
+/// Number of recent searches whose ranked results are kept for paging
+const MAX_CACHED_SEARCHES: usize = 8;
+/// Number of "did you mean" queries offered for a search without results
+const MAX_SPELLING_SUGGESTIONS: usize = 3;
+/// Number of recent queries remembered for completion
+pub const MAX_RECENT_QUERIES: usize = 50;
+/// How long recent queries wait in memory before the config is saved with them
+const RECENT_QUERIES_SAVE_DELAY: Duration = Duration::from_secs(2);
+/// How often a regex search emits the matches found since its last batch
+const REGEX_BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Ranked results of a recent search, so further pages don't re-run the query
+struct CachedSearch {
+    id: u64,
+    /// Active workspace when it ran; a cursor from before a switch doesn't page its results
+    workspace: String,
+    query: String,
+    results: Vec<SearchResult>,
+    facets: SearchFacets,
+    error: Option<String>,
+}
+
+static SEARCH_CACHE: Lazy<Mutex<VecDeque<CachedSearch>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+static NEXT_SEARCH_ID: AtomicU64 = AtomicU64::new(1);
+/// Set while a save of the recent queries is scheduled
+static RECENT_QUERIES_SAVE_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// ID and cancellation flag of a running search session
+type SessionHandle = (u64, Arc<AtomicBool>);
+
+/// The newest search session, if it is still running
+static ACTIVE_SESSION: Lazy<Mutex<Option<SessionHandle>>> = Lazy::new(|| Mutex::new(None));
 
 /// Primary search function - uses launcher-style fuzzy matching
 /// 
-/// This matches characters in sequence, so "7r" finds "7 Rules of Power"
+/// This matches characters in sequence, so "7r" finds "7 Rules of Power".
+/// A regex scan that timed out is an error here, as partial results can't be flagged.
 pub fn search_files(query: String, limit: u32) -> Result<Vec<SearchResult>, String> {
+    match launcher_search(&query, limit as usize)? {
+        (_, Some(error)) => Err(error),
+        (results, None) => Ok(results),
+    }
+}
+
+/// Paged launcher search
+///
+/// The first call (no cursor) ranks the query once and caches the ordered results;
+/// the returned `next_cursor` then serves later pages from that cache. If the cursor
+/// has expired or the workspace was switched since, the query is re-run and the page
+/// is taken from the fresh results.
+pub fn search_files_page(query: String, limit: u32, cursor: Option<String>) -> Result<SearchPage, String> {
+    let limit = limit as usize;
+    let (cursor_id, offset) = match cursor.as_deref().map(parse_cursor) {
+        Some(Some(parsed)) => parsed,
+        Some(None) => return Err("Invalid search cursor".to_string()),
+        None => (0, 0),
+    };
+
+    let workspace = crate::commands::config::CONFIG.lock().map_err(|e| e.to_string())?.active_workspace.clone();
+    let mut cache = SEARCH_CACHE.lock().map_err(|e| e.to_string())?;
+    let cached = cache.iter().position(|c| c.id == cursor_id && c.workspace == workspace && c.query == query);
+    let position = match cached {
+        Some(position) => position,
+        None => {
+            drop(cache);
+            let (results, error) = launcher_search(&query, MAX_PAGED_RESULTS)?;
+            cache = SEARCH_CACHE.lock().map_err(|e| e.to_string())?;
+            if cache.len() >= MAX_CACHED_SEARCHES {
+                cache.pop_front();
+            }
+            cache.push_back(CachedSearch {
+                id: NEXT_SEARCH_ID.fetch_add(1, Ordering::SeqCst),
+                workspace,
+                query,
+                facets: tantivy_engine::facet_counts(&results),
+                results,
+                error,
+            });
+            cache.len() - 1
+        }
+    };
+
+    let search = &cache[position];
+    let total = search.results.len();
+    let end = offset.saturating_add(limit).min(total);
+    let results = search.results.get(offset..end).unwrap_or_default().to_vec();
+    let next_cursor = (end < total).then(|| format!("{}:{}", search.id, end));
+    let suggestions = if total == 0 { spelling_suggestions(&search.query) } else { Vec::new() };
+
+    Ok(SearchPage { results, next_cursor, total, suggestions, facets: search.facets.clone(), error: search.error.clone() })
+}
+
+/// Starts a streamed search session and returns its ID
+///
+/// Filename (launcher) results are emitted first, followed by content matches that
+/// weren't already sent. A regex (`re:`) search instead streams matching files in
+/// batches while it scans. The last event carries facet counts for refining the
+/// search. Starting a session cancels the previous one, which stops scanning and
+/// emits nothing further.
+pub fn start_search_session<F>(query: String, limit: u32, emit: F) -> Result<u64, String>
+where
+    F: Fn(SearchEvent) + Send + 'static,
+{
+    let search_id = NEXT_SEARCH_ID.fetch_add(1, Ordering::SeqCst);
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let mut active = ACTIVE_SESSION.lock().map_err(|e| e.to_string())?;
+    if let Some((_, previous)) = active.replace((search_id, cancelled.clone())) {
+        previous.store(true, Ordering::SeqCst);
+    }
+    drop(active);
+
+    record_recent_query(&query)?;
+    let filters = resolve_filters(&query)?;
+    thread::spawn(move || {
+        run_search_session(search_id, &query, filters, limit as usize, &cancelled, &emit);
+
+        // Forget the session unless a newer one already replaced it
+        if let Ok(mut active) = ACTIVE_SESSION.lock() {
+            if active.as_ref().is_some_and(|(id, _)| *id == search_id) {
+                *active = None;
+            }
+        }
+    });
+
+    Ok(search_id)
+}
+
+/// Cancels a search session if it is still running
+pub fn cancel_search_session(search_id: u64) -> Result<(), String> {
+    let mut active = ACTIVE_SESSION.lock().map_err(|e| e.to_string())?;
+    if active.as_ref().is_some_and(|(id, _)| *id == search_id) {
+        if let Some((_, cancelled)) = active.take() {
+            cancelled.store(true, Ordering::SeqCst);
+        }
+    }
+    Ok(())
+}
+
+/// Runs both phases of a search session, bailing out as soon as it is superseded
+fn run_search_session(
+    search_id: u64,
+    query: &str,
+    filters: SearchFilters,
+    limit: usize,
+    cancelled: &AtomicBool,
+    emit: &dyn Fn(SearchEvent),
+) {
+    let event = |phase: &str, results: Vec<SearchResult>, done: bool, error: Option<String>| SearchEvent {
+        search_id,
+        phase: phase.to_string(),
+        results,
+        done,
+        error,
+        suggestions: Vec::new(),
+        facets: None,
+    };
+
+    if filters.regex.is_some() {
+        let found = RefCell::new(Vec::new());
+        let (results, error) = run_regex_scan(&filters, limit, cancelled, &|batch| {
+            found.borrow_mut().extend(batch.iter().cloned());
+            emit(event("regex", batch, false, None));
+        });
+        if !cancelled.load(Ordering::SeqCst) {
+            found.borrow_mut().extend(results.iter().cloned());
+            let facets = Some(tantivy_engine::facet_counts(&found.borrow()));
+            emit(SearchEvent { facets, ..event("regex", results, true, error) });
+        }
+        return;
+    }
+
+    // Phase 1: fast filename matches; the facets count all of them, not just the first `limit`
+    let facet_counter = RefCell::new(FacetCounter::default());
+    let launcher_results = if filters.workspaces.is_empty() {
+        tantivy_engine::search_files_launcher_cancellable(filters.clone(), limit, cancelled, &mut facet_counter.borrow_mut())
+            .map_err(|e| e.to_string())
+    } else {
+        workspace::search_workspaces(&filters, limit, |engine, f| {
+            let results = engine.search_launcher_cancellable(f, limit, cancelled, Some(&mut facet_counter.borrow_mut()))?;
+            Ok(results.unwrap_or_default())
+        })
+        .map(|results| (!cancelled.load(Ordering::SeqCst)).then_some(results))
+    };
+    let filename_results = match launcher_results {
+        Ok(Some(results)) => results,
+        Ok(None) => return, // Superseded by a newer search
+        Err(e) => {
+            emit(event("filename", Vec::new(), true, Some(e)));
+            return;
+        }
+    };
+    if cancelled.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let has_content_phase = !filters.query.is_empty();
+    let sent_paths: HashSet<String> = filename_results.iter().map(|r| r.path.clone()).collect();
+    if !has_content_phase {
+        let facets = Some(facet_counter.borrow().facets());
+        emit(SearchEvent { facets, ..event("filename", filename_results, true, None) });
+        return;
+    }
+    emit(event("filename", filename_results, false, None));
+
+    // Phase 2: slower content matches, skipping anything already sent
+    let content_results = if filters.workspaces.is_empty() {
+        tantivy_engine::search_files_content(&filters, limit, cancelled).map_err(|e| e.to_string())
+    } else {
+        // Matching lines are read once the workspaces' hits are merged, as `search_files_content` does
+        let results = workspace::search_workspaces(&filters, limit, |engine, f| {
+            Ok(engine.search_content_cancellable(&f, limit, cancelled)?.unwrap_or_default())
+        });
+        results.and_then(|mut results| {
+            let terms = tantivy_engine::line_match_terms(&filters.query, &filters.synonyms).map_err(|e| e.to_string())?;
+            tantivy_engine::add_line_matches(&mut results, &terms);
+            Ok(Some(results))
+        })
+    };
+    if cancelled.load(Ordering::SeqCst) {
+        return;
+    }
+    match content_results {
+        Ok(None) => {} // Superseded by a newer search
+        Ok(Some(results)) => {
+            let new_results: Vec<SearchResult> = results.into_iter().filter(|r| !sent_paths.contains(&r.path)).collect();
+            let suggestions = if sent_paths.is_empty() && new_results.is_empty() {
+                spelling_suggestions(query)
+            } else {
+                Vec::new()
+            };
+            let mut facet_counter = facet_counter.borrow_mut();
+            new_results.iter().for_each(|result| facet_counter.add(result));
+            let facets = Some(facet_counter.facets());
+            emit(SearchEvent { suggestions, facets, ..event("content", new_results, true, None) });
+        }
+        Err(e) => emit(event("content", Vec::new(), true, Some(e))),
+    }
+}
+
+/// Scans files for a regex search, passing matches to `emit_batch` as they are found
+///
+/// Returns the matches not yet passed on, plus an error for an invalid pattern or a
+/// scan that ran out of time (its results are then incomplete).
+fn run_regex_scan(
+    filters: &SearchFilters,
+    limit: usize,
+    cancelled: &AtomicBool,
+    emit_batch: &dyn Fn(Vec<SearchResult>),
+) -> (Vec<SearchResult>, Option<String>) {
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        let scan = scope.spawn(move || tantivy_engine::grep_files(filters, limit, cancelled, &sender));
+
+        let mut batch = Vec::new();
+        let mut last_batch = Instant::now();
+        loop {
+            match receiver.recv_timeout(REGEX_BATCH_INTERVAL) {
+                Ok(result) => batch.push(result),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            if !batch.is_empty() && last_batch.elapsed() >= REGEX_BATCH_INTERVAL && !cancelled.load(Ordering::SeqCst) {
+                emit_batch(std::mem::take(&mut batch));
+                last_batch = Instant::now();
+            }
+        }
+
+        let error = match scan.join() {
+            Ok(Ok(summary)) if summary.timed_out => Some(regex_timeout_error()),
+            Ok(Ok(_)) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(_) => Some("Regex search failed".to_string()),
+        };
+        (batch, error)
+    })
+}
+
+fn regex_timeout_error() -> String {
+    format!(
+        "Regex search stopped after {} seconds; results are incomplete",
+        tantivy_engine::REGEX_SEARCH_TIMEOUT.as_secs()
+    )
+}
+
+/// Completions for the query being typed, labelled by source
+///
+/// Matching recent queries come first, then the last word completed from indexed
+/// file names, then from frequent content terms.
+pub fn suggest(prefix: String, limit: u32) -> Result<Vec<QuerySuggestion>, String> {
+    let limit = limit as usize;
+    let prefix = prefix.trim_start();
+    if prefix.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut suggestions: Vec<QuerySuggestion> = Vec::new();
+    let recent = crate::commands::config::get_config()?.recent_queries;
+    let lowercase_prefix = prefix.to_lowercase();
+    for query in recent.into_iter().rev() {
+        if query.to_lowercase().starts_with(&lowercase_prefix) && query.len() > prefix.len() {
+            suggestions.push(QuerySuggestion { text: query, source: SuggestionSource::RecentQuery });
+        }
+    }
+
+    // A trailing space means the last word is finished; there is nothing to complete
+    let (context, last_word) = match prefix.rsplit_once(char::is_whitespace) {
+        Some((context, last_word)) => (format!("{} ", context), last_word),
+        None => (String::new(), prefix),
+    };
+    if !last_word.is_empty() {
+        let completions = tantivy_engine::complete_prefix(last_word, limit).map_err(|e| e.to_string())?;
+        let index_suggestions = completions
+            .filename_terms
+            .into_iter()
+            .map(|term| (term, SuggestionSource::Filename))
+            .chain(completions.content_terms.into_iter().map(|term| (term, SuggestionSource::Content)));
+        for (term, source) in index_suggestions {
+            suggestions.push(QuerySuggestion { text: format!("{}{}", context, term), source });
+        }
+    }
+
+    let mut seen = HashSet::new();
+    suggestions.retain(|suggestion| seen.insert(suggestion.text.to_lowercase()));
+    suggestions.truncate(limit);
+    Ok(suggestions)
+}
+
+/// Remembers a query for completion, in the config so it survives restarts
+///
+/// Searches start on every keystroke, so a query that extends or shortens the previous
+/// one replaces it rather than filling the list with partial words, and the config is
+/// only saved once typing paused for `RECENT_QUERIES_SAVE_DELAY`.
+fn record_recent_query(query: &str) -> Result<(), String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(());
+    }
+
+    let mut config = crate::commands::config::CONFIG.lock().map_err(|e| e.to_string())?;
+    let recent = &mut config.recent_queries;
+    if recent.last().is_some_and(|last| last == query) {
+        return Ok(());
+    }
+    if recent.last().is_some_and(|last| query.starts_with(last.as_str()) || last.starts_with(query)) {
+        recent.pop();
+    }
+    recent.retain(|existing| existing != query);
+    if recent.len() >= MAX_RECENT_QUERIES {
+        recent.remove(0);
+    }
+    recent.push(query.to_string());
+    drop(config);
+
+    if !RECENT_QUERIES_SAVE_PENDING.swap(true, Ordering::SeqCst) {
+        thread::spawn(|| {
+            thread::sleep(RECENT_QUERIES_SAVE_DELAY);
+            RECENT_QUERIES_SAVE_PENDING.store(false, Ordering::SeqCst);
+            let saved = crate::commands::config::CONFIG
+                .lock()
+                .map_err(|e| e.to_string())
+                .and_then(|config| crate::commands::config::save_config(&config));
+            if let Err(e) = saved {
+                eprintln!("Failed to save recent queries: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// "Did you mean" queries for a search that found nothing
+///
+/// Suggestions are a convenience, so a failure here just means none are offered.
+fn spelling_suggestions(query: &str) -> Vec<String> {
+    tantivy_engine::suggest_spelling(query, MAX_SPELLING_SUGGESTIONS).unwrap_or_default()
+}
+
+/// Parses an opaque `"{search_id}:{offset}"` cursor
+fn parse_cursor(cursor: &str) -> Option<(u64, usize)> {
+    let (id, offset) = cursor.split_once(':')?;
+    Some((id.parse().ok()?, offset.parse().ok()?))
+}
+
+/// Parses a query, resolving folder scopes against the configured folders
+///
+/// Cross-workspace queries (`ws:`) are left unresolved; each workspace resolves them
+/// against its own folders.
+fn resolve_filters(query: &str) -> Result<SearchFilters, String> {
+    let config = crate::commands::config::get_config()?;
+    let mut filters = tantivy_engine::parse_search_query(query);
+    // Resolve folder scopes like `under:work` against the configured indexed folders
+    if !filters.paths.is_empty() && filters.workspaces.is_empty() {
+        filters.resolve_folder_scopes(&config.indexed_folders);
+    }
+    filters.synonyms = config.synonyms;
+    Ok(filters)
+}
+
+/// Runs the launcher search for a raw query string
+///
+/// Alongside the results comes an error for a regex scan that timed out, whose
+/// results are incomplete.
+fn launcher_search(query: &str, limit: usize) -> Result<(Vec<SearchResult>, Option<String>), String> {
+    if query.trim().is_empty() {
+        return Ok((Vec::new(), None));
+    }
+
+    let filters = resolve_filters(query)?;
+    if filters.regex.is_some() {
+        let (sender, receiver) = mpsc::channel();
+        let summary = tantivy_engine::grep_files(&filters, limit, &AtomicBool::new(false), &sender).map_err(|e| e.to_string())?;
+        drop(sender);
+        return Ok((receiver.into_iter().collect(), summary.timed_out.then(regex_timeout_error)));
+    }
+    if !filters.workspaces.is_empty() {
+        return workspace::search_workspaces(&filters, limit, |engine, f| engine.search_launcher_with_filters(f, limit)).map(|results| (results, None));
+    }
+
     // Use launcher-style search for best UX (same as app launchers like Alfred/Raycast)
-    let results = tantivy_engine::search_files_launcher(&query, limit as usize)
-        .map_err(|e| e.to_string())?;
-    Ok(results)
+    tantivy_engine::search_files_launcher_with_filters(filters, limit)
+        .map(|results| (results, None))
+        .map_err(|e| e.to_string())
+}
+
+/// Files, bookmarks and history entries most similar to an indexed document
+pub fn related(path: String, limit: u32) -> Result<Vec<SearchResult>, String> {
+    tantivy_engine::related(&path, limit as usize).map_err(|e| e.to_string())
 }
 
 /// Search with specific options (fuzzy/prefix matching)
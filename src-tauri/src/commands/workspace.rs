@@ -0,0 +1,189 @@
+use crate::commands::config::{save_config, CONFIG};
+use crate::commands::jobs;
+use crate::commands::{Config, IndexJob, IndexJobKind, SearchResult, Workspace};
+use crate::services::file_watcher;
+use crate::services::tantivy_engine::{self, SearchFilters, TantivyEngine};
+
+/// Longest accepted workspace name
+const MAX_WORKSPACE_NAME_LEN: usize = 64;
+
+/// Points the global engine at the configured active workspace (called on startup)
+pub fn init() -> Result<(), String> {
+    let config = crate::commands::config::get_config()?;
+    tantivy_engine::switch_workspace(&config.active_workspace, config.excluded_folders)
+        .map_err(|e| e.to_string())
+}
+
+/// Lists all workspaces, the active one first
+pub fn list_workspaces() -> Result<Vec<Workspace>, String> {
+    let config = crate::commands::config::get_config()?;
+    Ok(all_workspaces(&config))
+}
+
+/// Creates an empty workspace; it gets its own index once it is switched to
+pub fn create_workspace(name: String) -> Result<Workspace, String> {
+    let name = name.trim().to_string();
+    validate_workspace_name(&name)?;
+
+    let mut config = CONFIG.lock().map_err(|e| e.to_string())?;
+    if all_workspaces(&config).iter().any(|ws| ws.name.eq_ignore_ascii_case(&name)) {
+        return Err(format!("Workspace '{}' already exists", name));
+    }
+
+    let workspace = Workspace {
+        name,
+        indexed_folders: Vec::new(),
+        excluded_folders: Vec::new(),
+        enable_history: false,
+        enable_bookmarks: false,
+    };
+    config.workspaces.push(workspace.clone());
+    save_config(&config)?;
+    Ok(workspace)
+}
+
+/// Deletes an inactive workspace together with its index
+pub fn delete_workspace(name: String) -> Result<(), String> {
+    let mut config = CONFIG.lock().map_err(|e| e.to_string())?;
+    if config.active_workspace.eq_ignore_ascii_case(&name) {
+        return Err("Can't delete the active workspace; switch to another one first".to_string());
+    }
+    let position = config
+        .workspaces
+        .iter()
+        .position(|ws| ws.name.eq_ignore_ascii_case(&name))
+        .ok_or_else(|| format!("Workspace '{}' not found", name))?;
+
+    let workspace = config.workspaces.remove(position);
+    save_config(&config)?;
+    drop(config);
+
+    tantivy_engine::delete_workspace_index(&workspace.name).map_err(|e| e.to_string())
+}
+
+/// Makes another workspace active and queues a scan of its folders; returns the job ID
+///
+/// Running index jobs are cancelled first so they don't write into the wrong index.
+/// The watcher is restarted on the new workspace's folders.
+pub fn switch_workspace<F>(name: String, emit: F) -> Result<u64, String>
+where
+    F: Fn(IndexJob) + Send + 'static,
+{
+    let folders = jobs::cancel_all_index_jobs(|| -> Result<Vec<String>, String> {
+        let mut config = CONFIG.lock().map_err(|e| e.to_string())?;
+        if config.active_workspace.eq_ignore_ascii_case(&name) {
+            return Err(format!("Workspace '{}' is already active", name));
+        }
+        let position = config
+            .workspaces
+            .iter()
+            .position(|ws| ws.name.eq_ignore_ascii_case(&name))
+            .ok_or_else(|| format!("Workspace '{}' not found", name))?;
+
+        let target = config.workspaces.remove(position);
+        let previous = active_workspace(&config);
+        config.workspaces.push(previous);
+        config.active_workspace = target.name;
+        config.indexed_folders = target.indexed_folders;
+        config.excluded_folders = target.excluded_folders;
+        config.enable_history = target.enable_history;
+        config.enable_bookmarks = target.enable_bookmarks;
+        save_config(&config)?;
+
+        tantivy_engine::switch_workspace(&config.active_workspace, config.excluded_folders.clone())
+            .map_err(|e| e.to_string())?;
+        Ok(config.indexed_folders.clone())
+    })??;
+
+    file_watcher::stop_watching()?;
+    if !folders.is_empty() {
+        file_watcher::start_watching(folders.clone())?;
+    }
+
+    // Catch up on changes made since the workspace was last active
+    jobs::start_index_job(IndexJobKind::StartupScan, folders, emit)
+}
+
+/// Runs a search against the workspaces named by `ws:` and merges the results
+///
+/// Each workspace is searched through its own read-only engine, with folder scopes resolved
+/// against its own folders. Results are labelled with their workspace, merged with
+/// the shared indexes' results, and deduplicated by path (a file indexed in several
+/// workspaces is listed once).
+pub fn search_workspaces(
+    filters: &SearchFilters,
+    limit: usize,
+    run: impl Fn(&TantivyEngine, SearchFilters) -> tantivy::Result<Vec<SearchResult>>,
+) -> Result<Vec<SearchResult>, String> {
+    let config = crate::commands::config::get_config()?;
+    let search_all = filters.workspaces.iter().any(|ws| ws == "all");
+
+    let mut results = Vec::new();
+    for workspace in all_workspaces(&config) {
+        if !search_all && !filters.workspaces.contains(&workspace.name.to_lowercase()) {
+            continue;
+        }
+
+        let mut workspace_filters = filters.clone();
+        workspace_filters.workspaces.clear();
+        if !workspace_filters.paths.is_empty() {
+            workspace_filters.resolve_folder_scopes(&workspace.indexed_folders);
+        }
+
+        // Searching must not create or reset a workspace's index, so one that was never
+        // built or has an outdated schema is left out until it is next activated
+        let index_path = tantivy_engine::workspace_index_path(&workspace.name);
+        if !index_path.exists() {
+            continue;
+        }
+        let engine = match TantivyEngine::open_read_only(index_path) {
+            Ok(engine) => engine,
+            Err(e) => {
+                eprintln!("Failed to open workspace '{}' for searching: {}", workspace.name, e);
+                continue;
+            }
+        };
+        for mut result in run(&engine, workspace_filters).map_err(|e| e.to_string())? {
+            result.workspace = Some(workspace.name.clone());
+            results.push(result);
+        }
+    }
+
+    let mut shared_filters = filters.clone();
+    shared_filters.workspaces.clear();
+    results.extend(
+        tantivy_engine::search_shared_indexes(|shared| run(shared, shared_filters.clone())).map_err(|e| e.to_string())?,
+    );
+    Ok(tantivy_engine::merge_results(results, &filters.sort, limit))
+}
+
+/// The active workspace's settings, which live in the top-level config fields
+fn active_workspace(config: &Config) -> Workspace {
+    Workspace {
+        name: config.active_workspace.clone(),
+        indexed_folders: config.indexed_folders.clone(),
+        excluded_folders: config.excluded_folders.clone(),
+        enable_history: config.enable_history,
+        enable_bookmarks: config.enable_bookmarks,
+    }
+}
+
+fn all_workspaces(config: &Config) -> Vec<Workspace> {
+    let mut workspaces = vec![active_workspace(config)];
+    workspaces.extend(config.workspaces.iter().cloned());
+    workspaces
+}
+
+/// Workspace names double as directory names and `ws:` values, so only simple names are allowed
+fn validate_workspace_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_WORKSPACE_NAME_LEN {
+        return Err(format!("Workspace names must be 1 to {} characters long", MAX_WORKSPACE_NAME_LEN));
+    }
+    if name.eq_ignore_ascii_case("all") {
+        return Err("'all' is reserved for searching every workspace".to_string());
+    }
+    if !name.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_')) {
+        return Err("Workspace names may only contain letters, digits, '-' and '_'".to_string());
+    }
+    Ok(())
+}
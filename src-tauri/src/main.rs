@@ -4,9 +4,10 @@ mod commands;
 mod services;
 mod utils;
 
-use commands::{index, search, config};
+use commands::{index, jobs, search, config, workspace};
 use services::tantivy_engine;
 use tauri::Manager;
+use tauri::Emitter;
 
 #[tauri::command]
 async fn search(query: String, limit: u32) -> Result<Vec<commands::SearchResult>, String> {
@@ -14,8 +15,42 @@ async fn search(query: String, limit: u32) -> Result<Vec<commands::SearchResult>
 }
 
 #[tauri::command]
-async fn add_folder(path: String) -> Result<(), String> {
-    index::add_indexed_folder(path)
+async fn search_page(query: String, limit: u32, cursor: Option<String>) -> Result<commands::SearchPage, String> {
+    search::search_files_page(query, limit, cursor)
+}
+
+#[tauri::command]
+fn start_search(app: tauri::AppHandle, query: String, limit: u32) -> Result<u64, String> {
+    search::start_search_session(query, limit, move |event| {
+        let _ = app.emit(search::SEARCH_RESULTS_EVENT, event);
+    })
+}
+
+#[tauri::command]
+async fn suggest(prefix: String, limit: u32) -> Result<Vec<commands::QuerySuggestion>, String> {
+    search::suggest(prefix, limit)
+}
+
+#[tauri::command]
+async fn related(path: String, limit: u32) -> Result<Vec<commands::SearchResult>, String> {
+    search::related(path, limit)
+}
+
+#[tauri::command]
+fn cancel_search(search_id: u64) -> Result<(), String> {
+    search::cancel_search_session(search_id)
+}
+
+/// Forwards index job progress to the frontend
+fn job_progress_emitter(app: tauri::AppHandle) -> impl Fn(commands::IndexJob) + Send + 'static {
+    move |job| {
+        let _ = app.emit(jobs::INDEX_PROGRESS_EVENT, job);
+    }
+}
+
+#[tauri::command]
+async fn add_folder(app: tauri::AppHandle, path: String) -> Result<u64, String> {
+    index::add_indexed_folder(path, job_progress_emitter(app))
 }
 
 #[tauri::command]
@@ -29,8 +64,68 @@ fn get_folders() -> Result<Vec<String>, String> {
 }
 
 #[tauri::command]
-async fn reindex() -> Result<(), String> {
-    index::rebuild_index()
+async fn reindex(app: tauri::AppHandle) -> Result<u64, String> {
+    index::rebuild_index(job_progress_emitter(app))
+}
+
+#[tauri::command]
+fn list_index_jobs() -> Result<Vec<commands::IndexJob>, String> {
+    jobs::list_index_jobs()
+}
+
+#[tauri::command]
+fn pause_index_job(job_id: u64) -> Result<(), String> {
+    jobs::pause_index_job(job_id)
+}
+
+#[tauri::command]
+fn resume_index_job(job_id: u64) -> Result<(), String> {
+    jobs::resume_index_job(job_id)
+}
+
+#[tauri::command]
+fn cancel_index_job(job_id: u64) -> Result<(), String> {
+    jobs::cancel_index_job(job_id)
+}
+
+#[tauri::command]
+async fn set_excluded_folders(folders: Vec<String>) -> Result<u32, String> {
+    index::set_excluded_folders(folders)
+}
+
+#[tauri::command]
+fn list_shared_indexes() -> Result<Vec<commands::SharedIndex>, String> {
+    index::list_shared_indexes()
+}
+
+#[tauri::command]
+async fn add_shared_index(label: String, path: String) -> Result<(), String> {
+    index::add_shared_index(label, path)
+}
+
+#[tauri::command]
+async fn remove_shared_index(label: String) -> Result<(), String> {
+    index::remove_shared_index(label)
+}
+
+#[tauri::command]
+fn list_workspaces() -> Result<Vec<commands::Workspace>, String> {
+    workspace::list_workspaces()
+}
+
+#[tauri::command]
+async fn create_workspace(name: String) -> Result<commands::Workspace, String> {
+    workspace::create_workspace(name)
+}
+
+#[tauri::command]
+async fn switch_workspace(app: tauri::AppHandle, name: String) -> Result<u64, String> {
+    workspace::switch_workspace(name, job_progress_emitter(app))
+}
+
+#[tauri::command]
+async fn delete_workspace(name: String) -> Result<(), String> {
+    workspace::delete_workspace(name)
 }
 
 #[tauri::command]
@@ -43,6 +138,41 @@ fn get_index_stats() -> Result<services::tantivy_engine::IndexStats, String> {
     index::get_index_stats()
 }
 
+#[tauri::command]
+async fn export_snapshot(path: String) -> Result<services::tantivy_engine::SnapshotManifest, String> {
+    index::export_snapshot(path)
+}
+
+#[tauri::command]
+async fn import_snapshot(
+    app: tauri::AppHandle,
+    path: String,
+    rebase_from: Option<String>,
+    rebase_to: Option<String>,
+) -> Result<u64, String> {
+    index::import_snapshot(path, rebase_from, rebase_to, job_progress_emitter(app))
+}
+
+#[tauri::command]
+async fn merge_segments() -> Result<services::tantivy_engine::MaintenanceResult, String> {
+    index::merge_index_segments()
+}
+
+#[tauri::command]
+async fn garbage_collect() -> Result<services::tantivy_engine::MaintenanceResult, String> {
+    index::garbage_collect_index()
+}
+
+#[tauri::command]
+async fn verify_index() -> Result<services::tantivy_engine::IndexVerification, String> {
+    index::verify_index()
+}
+
+#[tauri::command]
+async fn find_duplicates() -> Result<Vec<services::tantivy_engine::DuplicateGroup>, String> {
+    index::find_duplicate_files()
+}
+
 #[tauri::command]
 fn get_supported_extensions() -> services::tantivy_engine::SupportedExtensions {
     index::get_supported_extensions()
@@ -60,7 +190,14 @@ async fn set_hotkey(modifiers: Vec<String>, key: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-async fn open_file(path: String) -> Result<(), String> {
+async fn open_file(path: String, line: Option<u64>) -> Result<(), String> {
+    // Go straight to the line when an editor is configured
+    if let Some(line) = line {
+        if config::open_in_editor(&path, line)? {
+            return Ok(());
+        }
+    }
+
     #[cfg(target_os = "windows")]
     {
         use std::process::Command;
@@ -74,10 +211,17 @@ async fn open_file(path: String) -> Result<(), String> {
 
 fn main() {
     tantivy_engine::init().expect("Failed to initialize Tantivy");
+    if let Err(e) = workspace::init() {
+        eprintln!("Failed to open the active workspace: {}", e);
+    }
+    if let Err(e) = index::open_shared_indexes() {
+        eprintln!("Failed to open shared indexes: {}", e);
+    }
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .setup(|app| {
+            let handle = app.handle().clone();
             // Trigger initial scan and start watcher in background
             tauri::async_runtime::spawn(async move {
                 if let Ok(cfg) = commands::config::get_config() {
@@ -87,14 +231,11 @@ fn main() {
                             eprintln!("Failed to start file watcher: {}", e);
                         }
                         
-                        // 2. Initial scan to index missed files
-                        println!("Starting initial index scan...");
-                        for folder in cfg.indexed_folders {
-                            if let Err(e) = services::tantivy_engine::index_folder(&folder) {
-                                eprintln!("Failed to scan folder {}: {}", folder, e);
-                            }
+                        // 2. Initial scan to index missed files, reported like any other index job
+                        let emit = job_progress_emitter(handle);
+                        if let Err(e) = jobs::start_index_job(commands::IndexJobKind::StartupScan, cfg.indexed_folders, emit) {
+                            eprintln!("Failed to start initial index scan: {}", e);
                         }
-                        println!("Initial index scan complete.");
                     }
                 }
             });
@@ -103,17 +244,42 @@ fn main() {
 
         .invoke_handler(tauri::generate_handler![
             search,
+            search_page,
+            start_search,
+            cancel_search,
+            related,
+            suggest,
             add_folder,
             remove_folder,
             get_folders,
             reindex,
+            list_index_jobs,
+            pause_index_job,
+            resume_index_job,
+            cancel_index_job,
+            set_excluded_folders,
+            list_shared_indexes,
+            add_shared_index,
+            remove_shared_index,
+            list_workspaces,
+            create_workspace,
+            switch_workspace,
+            delete_workspace,
             get_document_count,
             get_index_stats,
+            export_snapshot,
+            import_snapshot,
+            merge_segments,
+            garbage_collect,
+            verify_index,
+            find_duplicates,
             get_config,
 
             set_hotkey,
             set_history_enabled,
             set_bookmarks_enabled,
+            set_synonyms,
+            set_editor_command,
             get_browser_status,
             get_supported_extensions,
             open_file
@@ -132,6 +298,16 @@ async fn set_bookmarks_enabled(enabled: bool) -> Result<(), String> {
     config::set_bookmarks_enabled(enabled)
 }
 
+#[tauri::command]
+async fn set_synonyms(groups: Vec<Vec<String>>) -> Result<(), String> {
+    config::set_synonyms(groups)
+}
+
+#[tauri::command]
+async fn set_editor_command(command: Option<String>) -> Result<(), String> {
+    config::set_editor_command(command)
+}
+
 #[tauri::command]
 fn get_browser_status() -> Result<services::browser_extractor::BrowserStatus, String> {
     config::get_browser_status()
@@ -295,3 +295,9 @@ pub fn remove_watch_folder(folder: &str) -> Result<(), String> {
 pub fn is_running() -> bool {
     WATCHER_RUNNING.load(Ordering::SeqCst)
 }
+
+/// Folders the global file watcher is watching (empty when it isn't running)
+pub fn watched_folders() -> Result<Vec<String>, String> {
+    let watcher = FILE_WATCHER.lock().map_err(|e| e.to_string())?;
+    Ok(watcher.watched_folders.clone())
+}
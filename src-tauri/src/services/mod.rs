@@ -2,4 +2,5 @@ pub mod tantivy_engine;
 pub mod file_watcher;
 pub mod hotkey_manager;
 pub mod browser_extractor;
+pub mod symbol_extractor;
 
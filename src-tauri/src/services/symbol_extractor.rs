@@ -0,0 +1,180 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// A definition found in a source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    /// "function", "method", "struct", "class", "interface", "enum", "trait", "type",
+    /// "module", "constant" or "macro"
+    pub kind: String,
+    /// 1-based line of the definition
+    pub line: u64,
+}
+
+/// One line pattern: capture group `name` is the symbol name, `kind` (when present)
+/// its keyword, mapped through `kind_of_keyword`; otherwise `kind` is used
+struct Pattern {
+    regex: Regex,
+    kind: &'static str,
+}
+
+fn pattern(regex: &str, kind: &'static str) -> Pattern {
+    Pattern { regex: Regex::new(regex).expect("invalid symbol pattern"), kind }
+}
+
+static RUST: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:(?:async|const|unsafe|extern\s+\x22[^\x22]*\x22)\s+)*(?P<kind>fn|struct|enum|trait|type|mod|const|static|union)\s+(?P<name>[A-Za-z_]\w*)", ""),
+    pattern(r"^\s*macro_rules!\s*(?P<name>[A-Za-z_]\w*)", "macro"),
+]);
+
+static PYTHON: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"^\s*(?:async\s+)?(?P<kind>def|class)\s+(?P<name>[A-Za-z_]\w*)", ""),
+]);
+
+static JAVASCRIPT: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(?P<kind>function|class|interface|enum|type)\*?\s+(?P<name>[A-Za-z_$][\w$]*)", ""),
+    // Top-level arrow functions and function expressions
+    pattern(r"^(?:export\s+)?(?:const|let|var)\s+(?P<name>[A-Za-z_$][\w$]*)\s*(?::[^=]+)?=\s*(?:async\s+)?(?:function\b|\([^)]*\)\s*(?::[^=]+)?=>|[A-Za-z_$][\w$]*\s*=>)", "function"),
+]);
+
+static GO: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"^func\s+\([^)]*\)\s*(?P<name>[A-Za-z_]\w*)", "method"),
+    pattern(r"^func\s+(?P<name>[A-Za-z_]\w*)", "function"),
+    pattern(r"^type\s+(?P<name>[A-Za-z_]\w*)\s+(?P<kind>struct|interface)", ""),
+    pattern(r"^type\s+(?P<name>[A-Za-z_]\w*)", "type"),
+]);
+
+/// Java, Kotlin, Scala, Swift, Dart and PHP declare types with similar keywords
+static JVM_LIKE: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"^\s*(?:(?:public|private|protected|internal|static|final|abstract|sealed|open|data|case|inline)\s+)*(?P<kind>class|interface|enum|record|object|trait|struct|protocol|extension)\s+(?P<name>[A-Za-z_]\w*)", ""),
+    pattern(r"^\s*(?:(?:public|private|protected|internal|static|final|abstract|override|open|suspend|inline)\s+)*(?:fun|func|def|function)\s+(?:<[^>]*>\s*)?(?P<name>[A-Za-z_]\w*)", "function"),
+    pattern(r"^\s*(?:(?:public|private|protected|static|final|abstract|synchronized)\s+)+[\w<>\[\],.?]+\s+(?P<name>[A-Za-z_]\w*)\s*\([^;]*$", "method"),
+]);
+
+static C_LIKE: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"^\s*(?:typedef\s+)?(?P<kind>struct|class|enum|union|namespace)\s+(?P<name>[A-Za-z_]\w*)\s*(?:[:{]|$)", ""),
+    pattern(r"^#\s*define\s+(?P<name>[A-Za-z_]\w*)", "macro"),
+    // Definitions start in the first column and don't end in `;` (that would be a declaration)
+    pattern(r"^(?:[A-Za-z_][\w:<>,]*[\s*&]+)+(?P<name>[A-Za-z_][\w:~]*)\s*\([^;]*$", "function"),
+]);
+
+static RUBY: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"^\s*(?P<kind>def|class|module)\s+(?:self\.)?(?P<name>[A-Za-z_][\w?!]*)", ""),
+]);
+
+static SHELL: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"^\s*(?:function\s+)?(?P<name>[A-Za-z_][\w-]*)\s*\(\)\s*\{?", "function"),
+    pattern(r"^\s*function\s+(?P<name>[A-Za-z_][\w-]*)", "function"),
+]);
+
+static LUA: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"^\s*(?:local\s+)?function\s+(?P<name>[A-Za-z_][\w.:]*)", "function"),
+]);
+
+static PERL: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"^\s*sub\s+(?P<name>[A-Za-z_]\w*)", "function"),
+    pattern(r"^\s*package\s+(?P<name>[A-Za-z_][\w:]*)", "module"),
+]);
+
+static SQL: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"(?i)^\s*create\s+(?:or\s+replace\s+)?(?P<kind>table|view|function|procedure|index)\s+(?:if\s+not\s+exists\s+)?(?P<name>[\w.\x22`\[\]]+)", ""),
+]);
+
+static R: Lazy<Vec<Pattern>> = Lazy::new(|| vec![
+    pattern(r"^\s*(?P<name>[A-Za-z_.][\w.]*)\s*(?:<-|=)\s*function\b", "function"),
+]);
+
+/// Line patterns for a file extension; None for languages without definitions (text, data)
+fn patterns_for(extension: &str) -> Option<&'static [Pattern]> {
+    let patterns: &'static Lazy<Vec<Pattern>> = match extension {
+        "rs" => &RUST,
+        "py" => &PYTHON,
+        "js" | "ts" | "tsx" | "jsx" | "vue" | "svelte" => &JAVASCRIPT,
+        "go" => &GO,
+        "java" | "kt" | "scala" | "swift" | "dart" | "php" => &JVM_LIKE,
+        "c" | "cpp" | "h" | "hpp" => &C_LIKE,
+        "rb" => &RUBY,
+        "sh" | "ps1" => &SHELL,
+        "lua" => &LUA,
+        "pl" | "pm" => &PERL,
+        "sql" => &SQL,
+        "r" => &R,
+        _ => return None,
+    };
+    Some(patterns.as_slice())
+}
+
+/// Maps a declaration keyword to a symbol kind
+fn kind_of_keyword(keyword: &str) -> &'static str {
+    match keyword.to_lowercase().as_str() {
+        "fn" | "def" | "function" | "func" | "fun" | "procedure" => "function",
+        "struct" | "union" | "record" | "table" | "view" => "struct",
+        "class" | "object" => "class",
+        "interface" | "protocol" => "interface",
+        "enum" => "enum",
+        "trait" | "extension" => "trait",
+        "mod" | "module" | "namespace" => "module",
+        "const" | "static" => "constant",
+        "index" => "index",
+        _ => "type",
+    }
+}
+
+/// Extracts definition names from source code, one line at a time
+///
+/// This is deliberately lightweight: it recognizes the common declaration forms of
+/// each language by keyword, not by parsing, so unusual formatting can be missed.
+/// The first pattern that matches a line wins.
+pub fn extract_symbols(content: &str, extension: &str) -> Vec<Symbol> {
+    let Some(patterns) = patterns_for(&extension.to_lowercase()) else {
+        return Vec::new();
+    };
+
+    let mut symbols = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        for pattern in patterns {
+            let Some(captures) = pattern.regex.captures(line) else {
+                continue;
+            };
+            let name = captures["name"].trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'));
+            if name.is_empty() || is_keyword(name) {
+                break;
+            }
+            let kind = captures.name("kind").map_or(pattern.kind, |k| kind_of_keyword(k.as_str()));
+            symbols.push(Symbol { name: name.to_string(), kind: kind.to_string(), line: index as u64 + 1 });
+            break;
+        }
+    }
+    symbols
+}
+
+/// Control-flow words that the C-like and method patterns would otherwise take for names
+fn is_keyword(name: &str) -> bool {
+    matches!(name, "if" | "for" | "while" | "switch" | "return" | "catch" | "else" | "sizeof" | "new" | "delete")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_symbols() {
+        let rust = "pub struct SearchFilters {\n}\n\npub(crate) async fn parse_search_query(input: &str) {}\nconst MAX_HITS: usize = 3;\nmacro_rules! log_hit {}\n";
+        let symbols = extract_symbols(rust, "rs");
+        let found: Vec<(&str, &str, u64)> = symbols.iter().map(|s| (s.name.as_str(), s.kind.as_str(), s.line)).collect();
+        assert_eq!(found, vec![
+            ("SearchFilters", "struct", 1),
+            ("parse_search_query", "function", 4),
+            ("MAX_HITS", "constant", 5),
+            ("log_hit", "macro", 6),
+        ]);
+        let ts = "export async function loadConfig() {}\nexport const showRelated = async (path: string) => {\n  const inner = () => 1;\n};\ninterface SearchEvent {}";
+        let names: Vec<String> = extract_symbols(ts, "ts").into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["loadConfig", "showRelated", "SearchEvent"], "Nested helpers are skipped");
+        let c = "int main(int argc, char **argv) {\n    if (argc > 1) {\n        return run(argv);\n    }\n}\nvoid helper(void);";
+        let names: Vec<String> = extract_symbols(c, "c").into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["main"], "Calls, control flow and declarations aren't definitions");
+        assert!(extract_symbols("fn not_code() {}", "txt").is_empty());
+    }
+}
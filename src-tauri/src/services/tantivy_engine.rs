@@ -1,12 +1,108 @@
-use crate::commands::SearchResult;
+use crate::commands::{FacetCount, LineMatch, SearchFacets, SearchResult};
+use crate::services::symbol_extractor;
 use jieba_rs::Jieba;
+use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder, DFA};
 use std::fs;
-use std::path::Path;
-use std::time::UNIX_EPOCH;
-use tantivy::collector::TopDocs;
-use tantivy::query::{BooleanQuery, FuzzyTermQuery, Occur, Query, QueryParser, TermQuery};
-use tantivy::schema::{Field, Schema, TEXT, STRING, STORED, NumericOptions, Value as _, IndexRecordOption};
-use tantivy::{Index, IndexWriter, TantivyDocument, Term};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use std::ops::Bound;
+use tantivy::collector::{DocSetCollector, TopDocs};
+use tantivy::query::{AllQuery, BooleanQuery, BoostQuery, FuzzyTermQuery, MoreLikeThisQuery, Occur, Query, QueryParser, RangeQuery, RegexQuery, TermQuery};
+use tantivy::schema::{Field, OwnedValue, Schema, TextFieldIndexing, TextOptions, TEXT, STRING, STORED, INDEXED, FAST, Value as _, IndexRecordOption};
+use tantivy::tokenizer::{Language, LowerCaser, RemoveLongFilter, SimpleTokenizer, Stemmer, StopWordFilter, TextAnalyzer, Token, TokenStream, Tokenizer};
+use tantivy::{DocAddress, Index, IndexWriter, TantivyDocument, Term};
+
+/// How many launcher candidates are scanned between cancellation checks
+const CANCEL_CHECK_INTERVAL: usize = 512;
+/// Version of the index schema; bump whenever `new_with_path` changes the fields
+/// so that snapshots from an incompatible build are rejected on import
+pub const SCHEMA_VERSION: u32 = 9;
+/// Workspace whose index lives at the original (pre-workspace) location
+pub const DEFAULT_WORKSPACE: &str = "default";
+/// Version of the snapshot archive layout
+const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+/// Snapshot archive entry holding the `SnapshotManifest`
+const SNAPSHOT_MANIFEST: &str = "manifest.json";
+/// Snapshot archive directory holding the index files
+const SNAPSHOT_INDEX_DIR: &str = "index";
+/// Maximum number of offending paths listed by `verify_index`
+const MAX_VERIFY_SAMPLES: usize = 100;
+/// Files up to this size are hashed in full
+const HASH_FULL_LIMIT: u64 = 1024 * 1024;
+/// How long a background job's writes may stay uncommitted
+const JOB_COMMIT_INTERVAL: Duration = Duration::from_secs(10);
+/// Files a background job checks at most between commits
+const JOB_COMMIT_FILES: usize = 5_000;
+/// Coarsest file modification time resolution we expect (FAT stores mtimes in 2 s steps)
+const MTIME_RESOLUTION_SECS: i64 = 2;
+/// Bytes hashed from each end of files larger than `HASH_FULL_LIMIT`
+const HASH_SAMPLE_SIZE: u64 = 64 * 1024;
+/// Most files whose full-content hash is cached; the cache starts over once it is full
+const FULL_HASH_CACHE_LIMIT: usize = 10_000;
+/// Most distinctive terms of a document used to find related ones
+const RELATED_MAX_QUERY_TERMS: usize = 25;
+/// Query words shorter than this (in chars) are never spelling-corrected
+const MIN_CORRECTABLE_WORD_LEN: usize = 3;
+/// Tokenizer of the `content_en` field: lowercase, English stop words removed, stemmed
+const ENGLISH_TOKENIZER: &str = "english";
+/// Record type of definitions extracted from source files
+pub const SYMBOL_RECORD_TYPE: &str = "symbol";
+/// Most symbols recorded per file (generated code can define thousands)
+const MAX_SYMBOLS_PER_FILE: usize = 2000;
+/// Matching lines reported per content search result
+const MAX_LINE_MATCHES: usize = 3;
+/// Longest line excerpt returned with a line match
+const MAX_LINE_MATCH_CHARS: usize = 200;
+/// Characters kept before the first match when a long line has to be cut
+const LINE_MATCH_CONTEXT_CHARS: usize = 40;
+/// Matching lines reported per file by a regex (`re:`) search
+const MAX_REGEX_LINE_MATCHES: usize = 10;
+/// How long a regex search scans files before returning what it has found
+pub const REGEX_SEARCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Upper bound on the compiled size of a user-written regex
+const REGEX_SIZE_LIMIT: usize = 10 * 1024 * 1024;
+/// Most threads reading files for a regex search
+const MAX_REGEX_SCAN_THREADS: usize = 8;
+/// Tokenizer of the `content_code` field: whole identifiers plus their sub-words
+const CODE_TOKENIZER: &str = "code";
+/// Tokenizer of the `*_zh` completion fields: the words jieba finds in Chinese text
+const CHINESE_WORDS_TOKENIZER: &str = "chinese_words";
+/// Characters of a document looked at to detect its language
+const LANGUAGE_SAMPLE_CHARS: usize = 4096;
+/// Texts with fewer words than this are too short to judge by stop words
+const MIN_LANGUAGE_SAMPLE_WORDS: usize = 20;
+/// Frequent English words; a text where they are common enough is considered English
+const ENGLISH_MARKER_WORDS: &[&str] = &[
+    "the", "and", "of", "to", "in", "is", "it", "that", "for", "on", "with", "as", "are", "was",
+    "this", "be", "by", "or", "not", "an", "at", "from", "have", "you", "we", "can", "will",
+];
+/// Extensions listed in index statistics, largest document count first
+const MAX_STATS_EXTENSIONS: usize = 10;
+/// Extensions listed in search facets, most common first
+const MAX_FACET_EXTENSIONS: usize = 10;
+/// Folders listed in search facets, most common first
+const MAX_FACET_FOLDERS: usize = 5;
+/// Score multiplier for matches found through a synonym, so direct matches rank first
+const SYNONYM_SCORE_FACTOR: f32 = 0.9;
+/// Shortest non-Chinese prefix completed from the index (a single Chinese character is enough)
+const MIN_COMPLETION_PREFIX_LEN: usize = 2;
+
+/// Loading the jieba dictionary is slow, so it is done once and shared
+static JIEBA: Lazy<Jieba> = Lazy::new(Jieba::new);
+
+/// Builders of Levenshtein automata for one and two typos; creating one is expensive
+static TYPO_AUTOMATON_BUILDERS: Lazy<[LevenshteinAutomatonBuilder; 2]> =
+    Lazy::new(|| [LevenshteinAutomatonBuilder::new(1, false), LevenshteinAutomatonBuilder::new(2, false)]);
+
+/// Full-content hash of a file with the (size, mtime) it was read at
+type FullHashEntry = (u64, i64, u64);
+
+/// Full-content hashes of files above `HASH_FULL_LIMIT`, keyed by path; a `dup:` search
+/// re-verifies the same large files on every keystroke
+static FULL_HASH_CACHE: Lazy<Mutex<std::collections::HashMap<String, FullHashEntry>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
 
 /// TantivyEngine provides full-text search capabilities for WorkSentry.
 /// 
@@ -14,29 +110,57 @@ use tantivy::{Index, IndexWriter, TantivyDocument, Term};
 /// - Persistent index storage
 /// - Incremental indexing (only updates changed files)
 /// - Duplicate prevention (deletes old entries before re-adding)
-/// - Rich metadata (path, filename, content, extension, size, modified time)
+/// - Rich metadata (path, filename, content, extension, size, modified time, content hash)
 pub struct TantivyEngine {
     schema: Schema,
     path_field: Field,
     file_name_field: Field,
     content_field: Field,
+    /// Content of English documents again, analyzed with `ENGLISH_TOKENIZER`
+    content_en_field: Field,
+    /// Content of source files again, analyzed with `CODE_TOKENIZER`
+    content_code_field: Field,
+    /// Chinese file names and content again, as jieba words, so completions are a prefix scan
+    file_name_zh_field: Field,
+    content_zh_field: Field,
     extension_field: Field,
     size_field: Field,
     modified_time_field: Field,
+    content_hash_field: Field,
+    /// When the file was last read, to tell whether its mtime can be trusted
+    indexed_time_field: Field,
     url_field: Field,
     record_type_field: Field,
+    symbol_name_field: Field,
+    symbol_kind_field: Field,
+    line_field: Field,
+    /// Why a file's content couldn't be read; such files are retried once they change
+    /// on disk, or by a rebuild
+    index_error_field: Field,
     index_path: std::path::PathBuf,
+    /// Folder names or paths that are never indexed (see `is_excluded`)
+    exclusions: Vec<String>,
+    /// Set for shared indexes, which are only opened and searched, never created or written
+    read_only: bool,
+    /// Writer a background job keeps open between its batches (see `index_files`)
+    job_writer: Mutex<Option<JobWriter>>,
+}
+
+/// A background job's open writer and what it wrote since its last commit
+struct JobWriter {
+    writer: IndexWriter<TantivyDocument>,
+    last_commit: Instant,
+    files_since_commit: usize,
 }
 
 impl TantivyEngine {
     pub fn new() -> tantivy::Result<Self> {
-        // Use a persistent index path in the app's data directory
-        let index_path = dirs::data_dir()
-            .unwrap_or_else(|| std::env::temp_dir())
-            .join("worksentry")
-            .join("index");
+        Self::for_workspace(DEFAULT_WORKSPACE)
+    }
 
-        Self::new_with_path(index_path)
+    /// Creates a TantivyEngine over a workspace's own persistent index
+    pub fn for_workspace(name: &str) -> tantivy::Result<Self> {
+        Self::new_with_path(workspace_index_path(name))
     }
 
     /// Creates a new TantivyEngine with a custom index path (useful for tests)
@@ -49,13 +173,35 @@ impl TantivyEngine {
         let file_name_field = schema_builder.add_text_field("file_name", TEXT | STORED);
         // Content is tokenized but not stored (saves space)
         let content_field = schema_builder.add_text_field("content", TEXT);
+        // English content is also indexed stemmed, so "indexing" finds "indexed".
+        // It is only searched by term, so positions aren't kept.
+        let english_indexing = TextFieldIndexing::default()
+            .set_tokenizer(ENGLISH_TOKENIZER)
+            .set_index_option(IndexRecordOption::WithFreqs);
+        let content_en_field = schema_builder.add_text_field("content_en", TextOptions::default().set_indexing_options(english_indexing));
+        // Source code is also indexed by identifier parts, so "user name" finds getUserName.
+        // Like content_en, it is only searched by term.
+        let code_indexing = TextFieldIndexing::default()
+            .set_tokenizer(CODE_TOKENIZER)
+            .set_index_option(IndexRecordOption::WithFreqs);
+        let content_code_field = schema_builder.add_text_field("content_code", TextOptions::default().set_indexing_options(code_indexing));
+        // The default tokenizer keeps a run of Chinese characters as one term; for prefix
+        // completions Chinese names and content are also indexed as the words jieba finds
+        let chinese_indexing = TextFieldIndexing::default()
+            .set_tokenizer(CHINESE_WORDS_TOKENIZER)
+            .set_index_option(IndexRecordOption::Basic);
+        let file_name_zh_field = schema_builder.add_text_field("file_name_zh", TextOptions::default().set_indexing_options(chinese_indexing.clone()));
+        let content_zh_field = schema_builder.add_text_field("content_zh", TextOptions::default().set_indexing_options(chinese_indexing));
         // Extension for filtering
         let extension_field = schema_builder.add_text_field("extension", STRING | STORED);
-        // File size in bytes
-        let size_field = schema_builder.add_u64_field("size", NumericOptions::default() | STORED);
-        // Modified time as unix timestamp (for incremental indexing)
-        // Modified time as unix timestamp (for incremental indexing)
-        let modified_time_field = schema_builder.add_i64_field("modified_time", NumericOptions::default() | STORED);
+        // File size in bytes (indexed + fast for size: range filters)
+        let size_field = schema_builder.add_u64_field("size", INDEXED | FAST | STORED);
+        // Modified time as unix timestamp (for incremental indexing and modified: filters)
+        let modified_time_field = schema_builder.add_i64_field("modified_time", INDEXED | FAST | STORED);
+        // Fast content hash (for change detection and duplicate finding, 0 if unreadable)
+        let content_hash_field = schema_builder.add_u64_field("content_hash", INDEXED | FAST | STORED);
+        // Unix timestamp of when the file was read (see `index_if_changed`)
+        let indexed_time_field = schema_builder.add_i64_field("indexed_time", STORED);
         
         // New fields for Browser Integration
         // URL for bookmarks/history items
@@ -63,6 +209,15 @@ impl TantivyEngine {
         // Record type: "file", "bookmark", "history"
         let record_type_field = schema_builder.add_text_field("record_type", STRING | STORED);
 
+        // Symbol records: definitions in source files, keyed by their file's path so they
+        // are replaced and deleted with it. The name isn't tokenized, so symbols stay out
+        // of text search and are only found by the launcher with sym:
+        let symbol_name_field = schema_builder.add_text_field("symbol_name", STRING | STORED);
+        let symbol_kind_field = schema_builder.add_text_field("symbol_kind", STRING | STORED);
+        let line_field = schema_builder.add_u64_field("line", STORED);
+        // Set on files whose content couldn't be read (unreadable, not UTF-8), for index stats
+        let index_error_field = schema_builder.add_text_field("index_error", STRING | STORED);
+
         let schema = schema_builder.build();
 
         Ok(Self {
@@ -70,17 +225,63 @@ impl TantivyEngine {
             path_field,
             file_name_field,
             content_field,
+            content_en_field,
+            content_code_field,
+            file_name_zh_field,
+            content_zh_field,
             extension_field,
             size_field,
             modified_time_field,
+            content_hash_field,
+            indexed_time_field,
             url_field,
             record_type_field,
+            symbol_name_field,
+            symbol_kind_field,
+            line_field,
+            index_error_field,
             index_path,
+            exclusions: Vec::new(),
+            read_only: false,
+            job_writer: Mutex::new(None),
         })
     }
 
-    /// Gets or creates the Tantivy index
+    /// Opens an existing index read-only, e.g. a prebuilt index of a shared folder
+    ///
+    /// Unlike `new_with_path`, the directory is never created or recreated: a missing
+    /// index or one built with a different schema is an error.
+    pub fn open_read_only(index_path: std::path::PathBuf) -> tantivy::Result<Self> {
+        let mut engine = Self::new_with_path(index_path)?;
+        engine.read_only = true;
+        engine.get_index()?;
+        Ok(engine)
+    }
+
+    /// Sets the folder names or paths to skip when indexing
+    pub fn set_exclusions(&mut self, exclusions: Vec<String>) {
+        self.exclusions = exclusions;
+    }
+
+    /// Gets or creates the Tantivy index, with the custom tokenizers registered
     fn get_index(&self) -> tantivy::Result<Index> {
+        let index = self.open_or_create_index()?;
+        register_tokenizers(&index);
+        Ok(index)
+    }
+
+    fn open_or_create_index(&self) -> tantivy::Result<Index> {
+        if self.read_only {
+            let index = Index::open_in_dir(&self.index_path)?;
+            if index.schema() != self.schema {
+                return Err(tantivy::TantivyError::SchemaError(format!(
+                    "{} was built with a different schema",
+                    self.index_path.display()
+                )));
+            }
+            return Ok(index);
+        }
+
         if let Some(parent) = self.index_path.parent() {
             if !parent.exists() {
                 fs::create_dir_all(parent)?;
@@ -94,8 +295,8 @@ impl TantivyEngine {
         
         // Try to open existing index, if schema mismatch, recreate
         match Index::open_in_dir(&self.index_path) {
-            Ok(index) => Ok(index),
-            Err(_) => {
+            Ok(index) if index.schema() == self.schema => Ok(index),
+            _ => {
                 // Schema may have changed, recreate index
                 fs::remove_dir_all(&self.index_path)?;
                 fs::create_dir_all(&self.index_path)?;
@@ -118,32 +319,85 @@ impl TantivyEngine {
         fs::metadata(path).map(|m| m.len()).unwrap_or(0)
     }
 
-    /// Looks up the indexed modified time for a file path
-    fn get_indexed_mtime(&self, index: &Index, path_str: &str) -> tantivy::Result<Option<i64>> {
+    /// Looks up the stored modified time, size and content hash for a file path
+    fn get_indexed_metadata(&self, index: &Index, path_str: &str) -> tantivy::Result<Option<IndexedFileMeta>> {
         let reader = index.reader()?;
         let searcher = reader.searcher();
         
-        let term = Term::from_field_text(self.path_field, path_str);
-        let term_query = tantivy::query::TermQuery::new(term, tantivy::schema::IndexRecordOption::Basic);
-        
-        let top_docs = searcher.search(&term_query, &TopDocs::with_limit(1))?;
+        let top_docs = searcher.search(&self.record_query(path_str), &TopDocs::with_limit(1))?;
         
         if let Some((_, doc_address)) = top_docs.first() {
             let doc: TantivyDocument = searcher.doc(*doc_address)?;
-            for field_value in doc.field_values() {
-                if field_value.field() == self.modified_time_field {
-                    if let Some(mtime) = field_value.value().as_i64() {
-                        return Ok(Some(mtime));
-                    }
-                }
+            let modified_time = doc.get_first(self.modified_time_field).and_then(|v| v.as_i64());
+            let size = doc.get_first(self.size_field).and_then(|v| v.as_u64());
+            let content_hash = doc.get_first(self.content_hash_field).and_then(|v| v.as_u64());
+            let indexed_time = doc.get_first(self.indexed_time_field).and_then(|v| v.as_i64());
+            if let (Some(modified_time), Some(size), Some(content_hash)) = (modified_time, size, content_hash) {
+                return Ok(Some(IndexedFileMeta { modified_time, size, content_hash, indexed_time }));
             }
         }
         
         Ok(None)
     }
 
+    /// Matches the file or browser record with this path, but not the file's symbols
+    fn record_query(&self, path: &str) -> BooleanQuery {
+        BooleanQuery::new(vec![
+            (Occur::Must, Box::new(TermQuery::new(Term::from_field_text(self.path_field, path), IndexRecordOption::Basic))),
+            (Occur::MustNot, Box::new(TermQuery::new(Term::from_field_text(self.record_type_field, SYMBOL_RECORD_TYPE), IndexRecordOption::Basic))),
+        ])
+    }
+
+    fn is_symbol_doc(&self, doc: &TantivyDocument) -> bool {
+        doc.get_first(self.record_type_field).and_then(|v| v.as_str()) == Some(SYMBOL_RECORD_TYPE)
+    }
+
+    /// Adds a record for each definition in a source file
+    ///
+    /// Callers delete the file's path term first, which removes its old symbols too.
+    fn add_symbols(&self, writer: &IndexWriter, path_str: &str, content: &str, extension: &str, modified_time: i64) -> tantivy::Result<()> {
+        for symbol in symbol_extractor::extract_symbols(content, extension).into_iter().take(MAX_SYMBOLS_PER_FILE) {
+            let mut doc = TantivyDocument::new();
+            doc.add_text(self.path_field, path_str);
+            doc.add_text(self.symbol_name_field, &symbol.name);
+            doc.add_text(self.symbol_kind_field, &symbol.kind);
+            doc.add_u64(self.line_field, symbol.line);
+            doc.add_text(self.extension_field, extension);
+            doc.add_i64(self.modified_time_field, modified_time);
+            doc.add_text(self.record_type_field, SYMBOL_RECORD_TYPE);
+            writer.add_document(doc)?;
+        }
+        Ok(())
+    }
+
+    /// Adds a file's content, plus a copy analyzed for source code or English text
+    ///
+    /// Other text only goes into `content`; Chinese queries are segmented with jieba
+    /// instead. Chinese content also goes into `content_zh`, for completions.
+    fn add_content(&self, doc: &mut TantivyDocument, content: &str, extension: &str) {
+        doc.add_text(self.content_field, content);
+        if Self::contains_chinese(content) {
+            doc.add_text(self.content_zh_field, content);
+        }
+        if is_code_extension(extension) {
+            doc.add_text(self.content_code_field, content);
+        } else if detect_language(content) == ContentLanguage::English {
+            doc.add_text(self.content_en_field, content);
+        }
+    }
+
+    /// Adds a file name or page title, plus its jieba words if it is Chinese
+    fn add_file_name(&self, doc: &mut TantivyDocument, file_name: &str) {
+        doc.add_text(self.file_name_field, file_name);
+        if Self::contains_chinese(file_name) {
+            doc.add_text(self.file_name_zh_field, file_name);
+        }
+    }
+
     /// Indexes a single file, deleting any existing entry first (prevents duplicates)
-    fn index_single_file(&self, writer: &mut IndexWriter, path: &Path) -> tantivy::Result<bool> {
+    ///
+    /// `content_hash` is the file's `hash_file_content` if the caller already computed it.
+    fn index_single_file(&self, writer: &mut IndexWriter, path: &Path, content_hash: Option<u64>) -> tantivy::Result<bool> {
         let path_str = path.to_string_lossy().to_string();
         
         // Delete existing document with this path (prevents duplicates)
@@ -161,13 +415,20 @@ impl TantivyEngine {
             .to_lowercase();
         let size = self.get_file_size(path);
         let modified_time = self.get_file_mtime(path).unwrap_or(0);
+        let content_hash = content_hash.unwrap_or_else(|| hash_file_content(path).unwrap_or(0));
+        let indexed_time = chrono::Utc::now().timestamp();
         
         // For binary files, only index the filename (not content)
         // For text files, read and index the content
+        let mut index_error = None;
         let content = if self.is_text_indexable(&extension) {
             match self.read_file_content(path) {
                 Ok(c) => c,
-                Err(_) => String::new(), // Empty content if unreadable
+                Err(e) => {
+                    // Still indexed by name, with empty content
+                    index_error = Some(e.to_string());
+                    String::new()
+                }
             }
         } else {
             // For binary files (epub, pdf, etc.), we add the filename as content too
@@ -178,14 +439,20 @@ impl TantivyEngine {
         // Create and add document
         let mut doc = TantivyDocument::new();
         doc.add_text(self.path_field, &path_str);
-        doc.add_text(self.file_name_field, &file_name);
-        doc.add_text(self.content_field, &content);
+        self.add_file_name(&mut doc, &file_name);
+        self.add_content(&mut doc, &content, &extension);
         doc.add_text(self.extension_field, &extension);
         doc.add_u64(self.size_field, size);
         doc.add_i64(self.modified_time_field, modified_time);
+        doc.add_u64(self.content_hash_field, content_hash);
+        doc.add_i64(self.indexed_time_field, indexed_time);
         doc.add_text(self.record_type_field, "file");
+        if let Some(error) = &index_error {
+            doc.add_text(self.index_error_field, error);
+        }
         
         writer.add_document(doc)?;
+        self.add_symbols(writer, &path_str, &content, &extension, modified_time)?;
         Ok(true)
     }
 
@@ -193,7 +460,7 @@ impl TantivyEngine {
     /// Used by the file watcher for real-time updates
     pub fn index_file(&self, path_str: &str) -> tantivy::Result<bool> {
         let path = Path::new(path_str);
-        if !path.exists() || !path.is_file() {
+        if !path.exists() || !path.is_file() || is_excluded(path, &self.exclusions) {
             return Ok(false);
         }
         
@@ -207,15 +474,18 @@ impl TantivyEngine {
         }
         
         let index = self.get_index()?;
-        let mut writer: IndexWriter<TantivyDocument> = index.writer(50_000_000)?;
+        let mut writer = self.writer(&index)?;
         
-        let result = self.index_single_file(&mut writer, path)?;
+        let result = self.index_single_file(&mut writer, path, None)?;
         writer.commit()?;
         
         Ok(result)
     }
 
-    /// Indexes a folder, only updating files that have changed (incremental indexing)
+    /// Indexes a folder in one commit, only updating files that have changed
+    ///
+    /// The app indexes folders through background jobs (`index_files`) instead.
+    #[cfg(test)]
     pub fn index_folder(&self, folder: &str) -> tantivy::Result<()> {
         let path = Path::new(folder);
         if !path.exists() || !path.is_dir() {
@@ -223,55 +493,176 @@ impl TantivyEngine {
         }
 
         let index = self.get_index()?;
-        let mut writer = index.writer(50_000_000)?;
-        
-        self.index_folder_with_writer(&index, &mut writer, folder)?;
-        
+        let mut writer = self.writer(&index)?;
+        let mut changes = IndexChanges::default();
+        for entry_path in collect_indexable_files(folder, &self.exclusions) {
+            self.index_if_changed(&index, &mut writer, &entry_path, &mut changes)?;
+        }
+
         writer.commit()?;
         Ok(())
     }
 
-    /// Internal method to index a folder using an existing writer
-    fn index_folder_with_writer(&self, index: &Index, writer: &mut IndexWriter, folder: &str) -> tantivy::Result<u32> {
-        let path = Path::new(folder);
-        if !path.exists() || !path.is_dir() {
+    /// Indexes a batch of a background job's files, skipping unchanged ones
+    ///
+    /// Jobs release the engine between batches so searches stay responsive, but keep
+    /// one writer open across them: it is committed once `JOB_COMMIT_INTERVAL` passed
+    /// or `JOB_COMMIT_FILES` files were checked, and `commit_job_writes` commits the rest.
+    /// Until then the batch's changes aren't searchable.
+    pub fn index_files(&self, paths: &[PathBuf]) -> tantivy::Result<IndexChanges> {
+        let index = self.get_index()?;
+        let mut job_writer = self.job_writer.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+        let job = match job_writer.as_mut() {
+            Some(job) => job,
+            None => job_writer.insert(JobWriter {
+                writer: index.writer(50_000_000)?,
+                last_commit: Instant::now(),
+                files_since_commit: 0,
+            }),
+        };
+
+        let mut changes = IndexChanges::default();
+        for path in paths {
+            if path.is_file() && !is_excluded(path, &self.exclusions) {
+                self.index_if_changed(&index, &mut job.writer, path, &mut changes)?;
+            }
+        }
+
+        job.files_since_commit += paths.len();
+        if job.last_commit.elapsed() >= JOB_COMMIT_INTERVAL || job.files_since_commit >= JOB_COMMIT_FILES {
+            job.writer.commit()?;
+            job.last_commit = Instant::now();
+            job.files_since_commit = 0;
+        }
+        Ok(changes)
+    }
+
+    /// Commits and closes the writer a background job kept open, if any
+    pub fn commit_job_writes(&self) -> tantivy::Result<()> {
+        let job_writer = self.job_writer.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?.take();
+        if let Some(mut job) = job_writer {
+            job.writer.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Opens a writer, first committing a background job's writes
+    ///
+    /// An index can only have one writer at a time, so a write between two of a job's
+    /// batches (e.g. from the file watcher) takes over; the job opens a new one next batch.
+    fn writer(&self, index: &Index) -> tantivy::Result<IndexWriter<TantivyDocument>> {
+        self.commit_job_writes()?;
+        index.writer(50_000_000)
+    }
+
+    /// Indexes a file only if it is new or changed since it was last indexed (incremental)
+    ///
+    /// A file counts as changed when its mtime or size differs from the indexed one in
+    /// either direction (so restored backups are picked up). If both match, the content
+    /// hash is compared to catch edits within the one-second mtime resolution. Files
+    /// whose content couldn't be read last time follow the same rules, so they are only
+    /// retried once they change (or by a rebuild).
+    fn index_if_changed(&self, index: &Index, writer: &mut IndexWriter, path: &Path, changes: &mut IndexChanges) -> tantivy::Result<()> {
+        let path_str = path.to_string_lossy().to_string();
+        let indexed = self.get_indexed_metadata(index, &path_str).ok().flatten();
+
+        // A file written again within the mtime resolution of being read keeps its mtime,
+        // so only such files need their content hashed to catch the change
+        let mut content_hash = None;
+        let needs_update = match (self.get_file_mtime(path), &indexed) {
+            (Some(mtime), Some(indexed)) => {
+                mtime != indexed.modified_time
+                    || self.get_file_size(path) != indexed.size
+                    || (indexed.indexed_time.is_none_or(|read_at| mtime >= read_at - MTIME_RESOLUTION_SECS) && {
+                        content_hash = hash_file_content(path).ok();
+                        content_hash != Some(indexed.content_hash)
+                    })
+            }
+            (Some(_), None) => true, // New file
+            _ => true, // Unknown state, re-index to be safe
+        };
+
+        if needs_update && self.index_single_file(writer, path, content_hash)? {
+            if indexed.is_some() {
+                changes.updated += 1;
+            } else {
+                changes.added += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes indexed files under the given roots that no longer exist on disk
+    ///
+    /// Catches files deleted or renamed while the app wasn't running. Roots that are
+    /// missing entirely (e.g. an unplugged drive) are skipped rather than emptied.
+    /// Returns how many entries were removed.
+    pub fn purge_missing_files(&self, roots: &[String]) -> tantivy::Result<u32> {
+        let roots: Vec<&Path> = roots.iter().map(Path::new).filter(|root| root.is_dir()).collect();
+        if roots.is_empty() {
             return Ok(0);
         }
 
-        let mut indexed_count = 0u32;
+        let index = self.get_index()?;
+        let searcher = index.reader()?.searcher();
+        let mut writer = self.writer(&index)?;
+        // A path with several documents is still one file
+        let mut removed_paths = std::collections::HashSet::new();
 
-        for entry in walkdir::WalkDir::new(folder)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let entry_path = entry.path();
-            if entry_path.is_file() {
-                if let Some(ext) = entry_path.extension().and_then(|e| e.to_str()) {
-                    if self.is_indexable_ext(ext) {
-                        // Check if file needs to be re-indexed (incremental)
-                        let path_str = entry_path.to_string_lossy().to_string();
-                        let file_mtime = self.get_file_mtime(entry_path);
-                        let indexed_mtime = self.get_indexed_mtime(index, &path_str).ok().flatten();
-                        
-                        // Only re-index if file is new or modified
-                        let needs_update = match (file_mtime, indexed_mtime) {
-                            (Some(f), Some(i)) => f > i,
-                            (Some(_), None) => true, // New file
-                            _ => true, // Unknown state, re-index to be safe
-                        };
-                        
-                        if needs_update {
-                            if self.index_single_file(writer, entry_path)? {
-                                indexed_count += 1;
-                            }
-                        }
-                    }
+        for segment_reader in searcher.segment_readers() {
+            let store_reader = segment_reader.get_store_reader(1)?;
+            for doc_id in segment_reader.doc_ids_alive() {
+                let doc: TantivyDocument = store_reader.get(doc_id)?;
+                // Symbols go together with their file
+                if self.is_symbol_doc(&doc) {
+                    continue;
+                }
+                let Some(path_str) = doc.get_first(self.path_field).and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let path = Path::new(path_str);
+                if !removed_paths.contains(path_str) && roots.iter().any(|root| path.starts_with(root)) && !path.exists() {
+                    writer.delete_term(Term::from_field_text(self.path_field, path_str));
+                    removed_paths.insert(path_str.to_string());
+                }
+            }
+        }
+
+        writer.commit()?;
+        Ok(removed_paths.len() as u32)
+    }
+
+    /// Removes indexed files that fall under one of the engine's exclusions
+    ///
+    /// Run after the exclusions changed, so newly excluded folders disappear from
+    /// results without a rebuild. Returns how many entries were removed.
+    pub fn purge_excluded_files(&self) -> tantivy::Result<u32> {
+        if self.exclusions.is_empty() {
+            return Ok(0);
+        }
+
+        let index = self.get_index()?;
+        let searcher = index.reader()?.searcher();
+        let mut writer = self.writer(&index)?;
+        let mut removed_count = 0u32;
+
+        for segment_reader in searcher.segment_readers() {
+            let store_reader = segment_reader.get_store_reader(1)?;
+            for doc_id in segment_reader.doc_ids_alive() {
+                let doc: TantivyDocument = store_reader.get(doc_id)?;
+                let is_file = doc.get_first(self.record_type_field).and_then(|v| v.as_str()) == Some("file");
+                let Some(path_str) = doc.get_first(self.path_field).and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                if is_file && is_excluded(Path::new(path_str), &self.exclusions) {
+                    writer.delete_term(Term::from_field_text(self.path_field, path_str));
+                    removed_count += 1;
                 }
             }
         }
 
-        Ok(indexed_count)
+        writer.commit()?;
+        Ok(removed_count)
     }
 
     /// Checks if a file extension should be indexed (content + filename)
@@ -291,18 +682,13 @@ impl TantivyEngine {
 
     /// Reads file content, skipping files that are too large (>1MB)
     pub fn read_file_content(&self, path: &Path) -> Result<String, std::io::Error> {
-        if let Ok(metadata) = fs::metadata(path) {
-            if metadata.len() > 1024 * 1024 {
-                return Ok(String::new()); // Skip large files
-            }
-        }
-        fs::read_to_string(path)
+        read_text_file(path)
     }
 
     /// Indexes browser history and bookmarks
     pub fn index_browser_data(&self, data: Vec<crate::services::browser_extractor::BrowserData>) -> tantivy::Result<()> {
         let index = self.get_index()?;
-        let mut writer = index.writer(50_000_000)?; 
+        let mut writer = self.writer(&index)?;
 
         // Deduplicate input data by URL
         let mut seen_urls = std::collections::HashSet::new();
@@ -318,7 +704,7 @@ impl TantivyEngine {
             let mut doc = TantivyDocument::new();
             
             doc.add_text(self.path_field, &item.url);
-            doc.add_text(self.file_name_field, &item.title);
+            self.add_file_name(&mut doc, &item.title);
             doc.add_text(self.content_field, &item.url); 
             doc.add_text(self.extension_field, &item.source);
             doc.add_text(self.record_type_field, &item.data_type);
@@ -326,6 +712,7 @@ impl TantivyEngine {
             
             doc.add_u64(self.size_field, 0); 
             doc.add_i64(self.modified_time_field, 0);
+            doc.add_u64(self.content_hash_field, 0);
 
             writer.add_document(doc)?;
         }
@@ -334,6 +721,90 @@ impl TantivyEngine {
         Ok(())
     }
 
+    /// Converts a stored document into a search result (match positions left empty)
+    ///
+    /// Browser records keep their source profile in the extension field, so it is
+    /// reported as `source` for them rather than as an extension.
+    fn doc_to_result(&self, doc: &TantivyDocument, score: f32) -> SearchResult {
+        let mut path = String::new();
+        let mut file_name = String::new();
+        let mut record_type = "file".to_string();
+        let mut extension = String::new();
+        let mut url = String::new();
+        let mut size = 0u64;
+        let mut modified_time = 0i64;
+        let mut line = None;
+        let mut symbol_name = None;
+        let mut symbol_kind = None;
+
+        for field_value in doc.field_values() {
+            let field: Field = field_value.field();
+            let value = field_value.value();
+            if field == self.size_field {
+                size = value.as_u64().unwrap_or(0);
+            } else if field == self.line_field {
+                line = value.as_u64();
+            } else if field == self.modified_time_field {
+                modified_time = value.as_i64().unwrap_or(0);
+            } else if let Some(text) = value.as_str() {
+                if field == self.path_field {
+                    path = text.to_string();
+                } else if field == self.file_name_field {
+                    file_name = text.to_string();
+                } else if field == self.record_type_field {
+                    record_type = text.to_string();
+                } else if field == self.extension_field {
+                    extension = text.to_string();
+                } else if field == self.url_field {
+                    url = text.to_string();
+                } else if field == self.symbol_name_field {
+                    symbol_name = Some(text.to_string());
+                } else if field == self.symbol_kind_field {
+                    symbol_kind = Some(text.to_string());
+                }
+            }
+        }
+
+        // Symbols are listed by their name; the path still points to the file
+        if let Some(name) = symbol_name {
+            file_name = name;
+        }
+
+        let (extension, source) = if record_type == "file" || record_type == SYMBOL_RECORD_TYPE {
+            // Get extension from filename if not stored
+            let extension = if extension.is_empty() {
+                Path::new(&file_name)
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .unwrap_or("")
+                    .to_lowercase()
+            } else {
+                extension
+            };
+            (extension, None)
+        } else {
+            (String::new(), Some(extension).filter(|s| !s.is_empty()))
+        };
+
+        SearchResult {
+            path,
+            file_name,
+            score,
+            record_type,
+            url: Some(url).filter(|u| !u.is_empty()),
+            extension,
+            source,
+            size,
+            modified_time,
+            match_positions: Vec::new(),
+            workspace: None,
+            shared_index: None,
+            line,
+            symbol_kind,
+            line_matches: Vec::new(),
+        }
+    }
+
     /// Searches the index for matching documents
     pub fn search(&self, query: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
         if query.trim().is_empty() {
@@ -353,33 +824,15 @@ impl TantivyEngine {
         let top_docs_result: Vec<(f32, tantivy::DocAddress)> = searcher
             .search(&parsed_query, &TopDocs::with_limit(limit))?;
 
+        let query_lower = query.to_lowercase();
+        let query_parts: Vec<&str> = query_lower.split_whitespace().collect();
         let mut results = Vec::new();
 
         for (score, doc_address) in top_docs_result {
             let doc: TantivyDocument = searcher.doc(doc_address)?;
-            let mut path_result = String::new();
-            let mut file_name = String::new();
-            let mut record_type = "file".to_string();
-
-            for field_value in doc.field_values() {
-                let field: Field = field_value.field();
-                if let Some(text) = field_value.value().as_str() {
-                    if field == self.path_field {
-                        path_result = text.to_string();
-                    } else if field == self.file_name_field {
-                        file_name = text.to_string();
-                    } else if field == self.record_type_field {
-                        record_type = text.to_string();
-                    }
-                }
-            }
-
-            results.push(SearchResult {
-                path: path_result,
-                file_name,
-                score,
-                record_type,
-            });
+            let mut result = self.doc_to_result(&doc, score);
+            result.match_positions = filename_match_positions(&query_parts, &result.file_name);
+            results.push(result);
         }
 
         Ok(results)
@@ -390,8 +843,26 @@ impl TantivyEngine {
     /// - `fuzzy`: Enable fuzzy matching (allows typos, edit distance 1-2)
     /// - `prefix`: Enable prefix matching (partial word matches)
     pub fn search_enhanced(&self, query: &str, limit: usize, fuzzy: bool, prefix: bool) -> tantivy::Result<Vec<SearchResult>> {
+        let results = self.search_enhanced_with_synonyms(query, limit, fuzzy, prefix, &[], &AtomicBool::new(false))?;
+        Ok(results.unwrap_or_default())
+    }
+
+    /// `search_enhanced` that also matches configured synonyms of each token
+    ///
+    /// Synonym terms are boosted below 1, so documents matching the typed words rank
+    /// above ones found only through a synonym. Returns None if cancelled before the
+    /// matching documents were loaded.
+    fn search_enhanced_with_synonyms(
+        &self,
+        query: &str,
+        limit: usize,
+        fuzzy: bool,
+        prefix: bool,
+        synonyms: &[Vec<String>],
+        cancelled: &AtomicBool,
+    ) -> tantivy::Result<Option<Vec<SearchResult>>> {
         if query.trim().is_empty() {
-            return Ok(Vec::new());
+            return Ok(Some(Vec::new()));
         }
 
         let index = self.get_index()?;
@@ -401,7 +872,7 @@ impl TantivyEngine {
         let tokens = self.tokenize_query(query);
         
         if tokens.is_empty() {
-            return Ok(Vec::new());
+            return Ok(Some(Vec::new()));
         }
 
         // Build queries for each token
@@ -434,74 +905,204 @@ impl TantivyEngine {
                 let term = Term::from_field_text(field, &token_lower);
                 let term_query = TermQuery::new(term, IndexRecordOption::Basic);
                 subqueries.push((Occur::Should, Box::new(term_query)));
+
+                for synonym in synonyms_of(synonyms, &token_lower) {
+                    for synonym_token in self.tokenize_query(&synonym) {
+                        let term = Term::from_field_text(field, &synonym_token.to_lowercase());
+                        let term_query = TermQuery::new(term, IndexRecordOption::Basic);
+                        subqueries.push((Occur::Should, Box::new(BoostQuery::new(Box::new(term_query), SYNONYM_SCORE_FACTOR))));
+                    }
+                }
+            }
+
+            // Whole identifiers and identifier parts in source files
+            let term = Term::from_field_text(self.content_code_field, &token_lower);
+            subqueries.push((Occur::Should, Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))));
+
+            // Stemmed match against English documents; stop words analyze to nothing
+            for stem in english_terms(&token_lower) {
+                let term = Term::from_field_text(self.content_en_field, &stem);
+                subqueries.push((Occur::Should, Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))));
+            }
+            for synonym in synonyms_of(synonyms, &token_lower) {
+                for stem in english_terms(&synonym) {
+                    let term = Term::from_field_text(self.content_en_field, &stem);
+                    let term_query = TermQuery::new(term, IndexRecordOption::WithFreqs);
+                    subqueries.push((Occur::Should, Box::new(BoostQuery::new(Box::new(term_query), SYNONYM_SCORE_FACTOR))));
+                }
             }
         }
 
         // If no subqueries built, fall back to standard search
         if subqueries.is_empty() {
-            return self.search(query, limit);
+            return self.search(query, limit).map(Some);
         }
 
         let boolean_query = BooleanQuery::new(subqueries);
         
         let top_docs_result: Vec<(f32, tantivy::DocAddress)> = searcher
             .search(&boolean_query, &TopDocs::with_limit(limit))?;
+        if cancelled.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
 
+        let token_parts: Vec<String> = tokens.iter().map(|t| t.to_lowercase()).collect();
+        let token_parts: Vec<&str> = token_parts.iter().map(|t| t.as_str()).collect();
         let mut results = Vec::new();
 
         for (score, doc_address) in top_docs_result {
             let doc: TantivyDocument = searcher.doc(doc_address)?;
-            let mut path_result = String::new();
-            let mut file_name = String::new();
-            let mut record_type = "file".to_string();
-
-            for field_value in doc.field_values() {
-                let field: Field = field_value.field();
-                if let Some(text) = field_value.value().as_str() {
-                    if field == self.path_field {
-                        path_result = text.to_string();
-                    } else if field == self.file_name_field {
-                        file_name = text.to_string();
-                    } else if field == self.record_type_field {
-                        record_type = text.to_string();
+            let mut result = self.doc_to_result(&doc, score);
+            result.match_positions = filename_match_positions(&token_parts, &result.file_name);
+            results.push(result);
+        }
+        
+        Ok(Some(results))
+    }
+
+    /// Words looked for in the lines of content search hits (see `add_line_matches`)
+    ///
+    /// Query words, their synonyms and their English stems, lowercased.
+    fn line_match_terms(&self, query: &str, synonyms: &[Vec<String>]) -> Vec<String> {
+        let mut terms: Vec<String> = Vec::new();
+        for token in self.tokenize_query(query) {
+            let token = token.to_lowercase();
+            let mut words = vec![token.clone()];
+            for synonym in synonyms_of(synonyms, &token) {
+                words.extend(synonym.split_whitespace().map(str::to_string));
+            }
+            for word in words {
+                let stems = english_terms(&word).into_iter().filter(|stem| stem.chars().count() >= 3);
+                for term in std::iter::once(word.clone()).chain(stems) {
+                    if !terms.contains(&term) {
+                        terms.push(term);
                     }
                 }
             }
-
-            results.push(SearchResult {
-                path: path_result,
-                file_name,
-                score,
-                record_type,
-            });
         }
-        
+        terms
+    }
+
+    /// Content search (BM25 with fuzzy matching) restricted by launcher filters
+    ///
+    /// Extra candidates are fetched so that filtering still leaves up to `limit` results.
+    #[cfg(test)]
+    pub fn search_content(&self, filters: &SearchFilters, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
+        let mut results = self.search_content_cancellable(filters, limit, &AtomicBool::new(false))?.unwrap_or_default();
+        add_line_matches(&mut results, &self.line_match_terms(&filters.query, &filters.synonyms));
         Ok(results)
     }
 
+    /// `search_content` without the matching lines, which the caller adds outside the engine lock
+    ///
+    /// Used for the slower second phase of a streamed search; returns None if cancelled.
+    pub fn search_content_cancellable(
+        &self,
+        filters: &SearchFilters,
+        limit: usize,
+        cancelled: &AtomicBool,
+    ) -> tantivy::Result<Option<Vec<SearchResult>>> {
+        if filters.query.is_empty() {
+            return Ok(Some(Vec::new()));
+        }
+
+        let Some(candidates) = self.search_enhanced_with_synonyms(&filters.query, limit.saturating_mul(4), true, false, &filters.synonyms, cancelled)? else {
+            return Ok(None);
+        };
+        let duplicate_paths = if filters.duplicates_only {
+            let searcher = self.get_index()?.reader()?.searcher();
+            let doc_groups = self.duplicate_doc_groups(&searcher)?;
+            Some(self.duplicate_groups_with_paths(&searcher, &doc_groups)?.into_iter().flat_map(|group| group.paths).collect::<std::collections::HashSet<_>>())
+        } else {
+            None
+        };
+        let mut seen_paths = std::collections::HashSet::new();
+        let mut results: Vec<SearchResult> = candidates
+            .into_iter()
+            .filter(|r| duplicate_paths.as_ref().is_none_or(|paths| paths.contains(&r.path)))
+            .filter(|r| filters.matches_result(r) && seen_paths.insert(r.path.clone()))
+            .collect();
+        filters.sort.apply(&mut results);
+        results.truncate(limit);
+        Ok(Some(results))
+    }
+
+    /// Indexed text files that may match a regex (`re:`) search, newest first
+    ///
+    /// The words the regex requires (see `regex_required_words`) and any plain query
+    /// words must appear in the indexed content, so most files are ruled out without
+    /// reading them. A regex without such words leaves every text file as a candidate.
+    pub fn regex_candidates(&self, filters: &SearchFilters, pattern: &str) -> tantivy::Result<Vec<SearchResult>> {
+        let searcher = self.get_index()?.reader()?.searcher();
+        let file_term = Term::from_field_text(self.record_type_field, "file");
+        let mut subqueries: Vec<(Occur, Box<dyn Query>)> = vec![
+            (Occur::Must, Box::new(TermQuery::new(file_term, IndexRecordOption::Basic))),
+        ];
+        // Required words can sit inside longer indexed tokens, so match terms containing them
+        for word in regex_required_words(pattern) {
+            let term_pattern = format!(".*{}.*", regex_syntax::escape(&word));
+            subqueries.push((Occur::Must, Box::new(RegexQuery::from_pattern(&term_pattern, self.content_field)?)));
+        }
+        for token in self.tokenize_query(&filters.query) {
+            let term = Term::from_field_text(self.content_field, &token.to_lowercase());
+            subqueries.push((Occur::Must, Box::new(TermQuery::new(term, IndexRecordOption::Basic))));
+        }
+        if let Some(range_query) = self.build_range_filter_query(filters) {
+            subqueries.push((Occur::Must, Box::new(range_query)));
+        }
+
+        let mut candidates = Vec::new();
+        for address in searcher.search(&BooleanQuery::new(subqueries), &DocSetCollector)? {
+            let result = self.doc_to_result(&searcher.doc(address)?, 0.0);
+            if self.is_text_indexable(&result.extension) && filters.matches_result(&result) {
+                candidates.push(result);
+            }
+        }
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.modified_time));
+        Ok(candidates)
+    }
+
+    /// Parses `query` and runs a launcher-style search (see `search_launcher_with_filters`)
+    #[cfg(test)]
+    pub fn search_launcher(&self, query: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
+        if query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        self.search_launcher_with_filters(parse_search_query(query), limit)
+    }
+
     /// Launcher-style search that matches characters in sequence (like "7r" → "7 Rules")
-    /// 
+    ///
     /// This is the most flexible search mode, ideal for app launchers:
     /// - Characters in query should appear in order in filename
     /// - Spaces in query act as separators (each part must match)
     /// - Case-insensitive
-    /// 
-    /// Supports search operators:
-    /// - `ext:pdf,docx` - filter by specific file extensions
-    /// - `type:doc` - filter by type category (doc, app, image, video, audio, code, archive)
-    /// - `in:files` or `in:bookmarks` or `in:history` - filter by record type
-    pub fn search_launcher(&self, query: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
-        if query.trim().is_empty() {
-            return Ok(Vec::new());
-        }
+    ///
+    /// Query parts containing a path separator (`proj/src main`) are matched
+    /// against the directory components of the path as well as the filename.
+    /// `filters` come from `parse_search_query`, possibly resolved further.
+    pub fn search_launcher_with_filters(&self, filters: SearchFilters, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
+        let never_cancelled = AtomicBool::new(false);
+        Ok(self
+            .search_launcher_cancellable(filters, limit, &never_cancelled, None)?
+            .unwrap_or_default())
+    }
 
-        // Step 1: Parse filters from query
-        let filters = parse_search_query(query);
-        let has_filters = !filters.extensions.is_empty() || !filters.types.is_empty() || !filters.record_types.is_empty();
-        
+    /// Launcher-style search that stops early once `cancelled` is set
+    ///
+    /// The flag is checked while scanning candidates; returns None if the search
+    /// was cancelled before it finished. All hits, not just the first `limit`,
+    /// are added to `facets` if given.
+    pub fn search_launcher_cancellable(
+        &self,
+        filters: SearchFilters,
+        limit: usize,
+        cancelled: &AtomicBool,
+        facets: Option<&mut FacetCounter>,
+    ) -> tantivy::Result<Option<Vec<SearchResult>>> {
         // If no query text and no filters, return empty
-        if filters.query.is_empty() && !has_filters {
-            return Ok(Vec::new());
+        if filters.query.is_empty() && !filters.has_filters() {
+            return Ok(Some(Vec::new()));
         }
         
         let index = self.get_index()?;
@@ -514,148 +1115,479 @@ impl TantivyEngine {
             Some(filters.query.to_lowercase())
         };
 
-        // Step 2: Collect ALL matching documents with scores (no filtering yet)
-        let mut all_results: Vec<(SearchResult, String)> = Vec::new(); // (result, extension)
+        // Query parts with a path separator match directory components, the rest match the filename
+        let (path_parts, name_parts): (Vec<&str>, Vec<&str>) = search_query
+            .as_deref()
+            .map(|sq| sq.split_whitespace().partition(|part| part.contains(['/', '\\'])))
+            .unwrap_or_default();
+        // The filename query with one word swapped for a synonym, tried when the words themselves don't match
+        let synonym_variants = synonym_variants(&filters.synonyms, &name_parts);
+
+        // Step 2: Pick candidate documents. Size/date filters are resolved through
+        // the fast fields with a range query, so only matching docs are loaded;
+        // otherwise every live document is a candidate, except symbols unless asked for.
+        let candidates: Vec<DocAddress> = match self.build_range_filter_query(&filters) {
+            Some(range_query) => {
+                let mut addresses: Vec<DocAddress> = searcher
+                    .search(&range_query, &DocSetCollector)?
+                    .into_iter()
+                    .collect();
+                addresses.sort();
+                addresses
+            }
+            None => {
+                let symbol_query = TermQuery::new(Term::from_field_text(self.record_type_field, SYMBOL_RECORD_TYPE), IndexRecordOption::Basic);
+                let candidate_query: Box<dyn Query> = if filters.record_types.iter().all(|r| r == SYMBOL_RECORD_TYPE) && !filters.record_types.is_empty() {
+                    Box::new(symbol_query)
+                } else if filters.record_types.iter().any(|r| r == SYMBOL_RECORD_TYPE) {
+                    Box::new(AllQuery)
+                } else {
+                    Box::new(BooleanQuery::new(vec![
+                        (Occur::Must, Box::new(AllQuery)),
+                        (Occur::MustNot, Box::new(symbol_query)),
+                    ]))
+                };
+                let mut addresses: Vec<DocAddress> = searcher
+                    .search(&candidate_query, &DocSetCollector)?
+                    .into_iter()
+                    .collect();
+                addresses.sort();
+                addresses
+            }
+        };
 
-        for segment_reader in searcher.segment_readers() {
-            let store_reader = segment_reader.get_store_reader(1)?;
-            for doc_id in 0..segment_reader.num_docs() {
-                if let Ok(doc) = store_reader.get::<TantivyDocument>(doc_id) {
-                    let mut path_result = String::new();
-                    let mut file_name = String::new();
-                    let mut record_type = "file".to_string();
-                    let mut extension = String::new();
+        // dup: narrows the candidates to files that share their content hash with another file,
+        // then keeps only paths in a verified group (a path indexed twice is not a duplicate)
+        let (candidates, duplicate_paths) = if filters.duplicates_only {
+            let doc_groups = self.duplicate_doc_groups(&searcher)?;
+            let paths: std::collections::HashSet<String> =
+                self.duplicate_groups_with_paths(&searcher, &doc_groups)?.into_iter().flat_map(|group| group.paths).collect();
+            let duplicates: std::collections::HashSet<DocAddress> = doc_groups.into_iter().flatten().collect();
+            (candidates.into_iter().filter(|address| duplicates.contains(address)).collect(), Some(paths))
+        } else {
+            (candidates, None)
+        };
 
-                    for field_value in doc.field_values() {
-                        if field_value.field() == self.path_field {
-                            if let Some(text) = field_value.value().as_str() {
-                                path_result = text.to_string();
-                            }
-                        } else if field_value.field() == self.file_name_field {
-                            if let Some(text) = field_value.value().as_str() {
-                                file_name = text.to_string();
-                            }
-                        } else if field_value.field() == self.record_type_field {
-                             if let Some(text) = field_value.value().as_str() {
-                                record_type = text.to_string();
-                            }
-                        } else if field_value.field() == self.extension_field {
-                            if let Some(text) = field_value.value().as_str() {
-                                extension = text.to_string();
-                            }
-                        }
-                    }
+        // Step 3: Collect ALL matching documents with scores (no filtering yet)
+        let mut all_results: Vec<SearchResult> = Vec::new();
 
-                    if file_name.is_empty() {
-                        continue;
-                    }
+        for (scanned, doc_address) in candidates.into_iter().enumerate() {
+            if scanned % CANCEL_CHECK_INTERVAL == 0 && cancelled.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
 
-                    let file_name_lower = file_name.to_lowercase();
-                    
-                    // Calculate score based on text match
-                    let score = if let Some(ref sq) = search_query {
-                        let query_parts: Vec<&str> = sq.split_whitespace().collect();
-                        match Self::calculate_launcher_score(&query_parts, &file_name_lower) {
-                            Some(s) => s,
-                            None => continue, // No text match, skip
-                        }
-                    } else {
-                        // No search query, just filtering - use base score with extension bonus
-                        100.0
-                    };
+            if let Ok(doc) = searcher.doc::<TantivyDocument>(doc_address) {
+                let mut result = self.doc_to_result(&doc, 0.0);
+                if result.file_name.is_empty()
+                    || duplicate_paths.as_ref().is_some_and(|paths| !paths.contains(&result.path))
+                {
+                    continue;
+                }
 
-                    // Get extension from filename if not stored
-                    let file_ext = if extension.is_empty() && record_type == "file" {
-                        std::path::Path::new(&file_name)
-                            .extension()
-                            .and_then(|e| e.to_str())
-                            .unwrap_or("")
-                            .to_string()
-                    } else {
-                        extension
+                // Calculate score based on text match
+                let mut score = if !name_parts.is_empty() {
+                    let synonym_score = || {
+                        synonym_variants
+                            .iter()
+                            .filter_map(|variant| {
+                                let variant: Vec<&str> = variant.iter().map(String::as_str).collect();
+                                Self::calculate_launcher_score(&variant, &result.file_name)
+                            })
+                            .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+                            .map(|s| s * SYNONYM_SCORE_FACTOR)
                     };
-
-                    all_results.push((SearchResult {
-                        path: path_result,
-                        file_name,
-                        score,
-                        record_type,
-                    }, file_ext));
+                    match Self::calculate_launcher_score(&name_parts, &result.file_name).or_else(synonym_score) {
+                        Some(s) => s,
+                        None => continue, // No text match, skip
+                    }
+                } else {
+                    // No filename query, just filtering - use base score with extension bonus
+                    100.0
+                };
+
+                if !path_parts.is_empty() {
+                    match Self::calculate_path_score(&path_parts, &result.path.to_lowercase()) {
+                        Some(s) => score += s,
+                        None => continue, // Directory components don't match, skip
+                    }
                 }
+
+                result.score = score;
+                all_results.push(result);
             }
         }
 
-        // Step 3: Sort by score FIRST (ranking is independent of filtering)
-        all_results.sort_by(|a, b| b.0.score.partial_cmp(&a.0.score).unwrap_or(std::cmp::Ordering::Equal));
+        // Step 4: Sort by score FIRST (ranking is independent of filtering)
+        all_results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
 
-        // Step 4: Apply filters as post-processing (filter the sorted results)
+        // Step 5: Apply filters as post-processing (filter the sorted results)
         let filtered_results: Vec<SearchResult> = all_results
             .into_iter()
-            .filter(|(result, file_ext)| {
-                // Filter by record type (in:files, in:bookmarks, in:history)
-                if !matches_record_type_filter(&result.record_type, &filters) {
-                    return false;
-                }
-                
-                // Filter by extension/type
-                let has_extension_filter = !filters.extensions.is_empty() || !filters.types.is_empty();
-                if has_extension_filter {
-                    if result.record_type == "file" {
-                        // For files, check extension
-                        if !matches_extension_filter(file_ext, &filters) {
-                            return false;
-                        }
-                    } else {
-                        // Non-file records (URLs) don't have extensions, exclude them
-                        return false;
-                    }
-                }
-                
-                true
-            })
-            .map(|(result, _)| result)
+            .filter(|result| filters.matches_result(result))
             .collect::<Vec<_>>();
         
-        // Step 5: Deduplicate by path (in case of duplicate entries in index)
+        // Step 6: Deduplicate by path (in case of duplicate entries in index); symbols by path and line
         let mut seen_paths = std::collections::HashSet::new();
-        let deduplicated_results: Vec<SearchResult> = filtered_results
+        let mut deduplicated_results: Vec<SearchResult> = filtered_results
             .into_iter()
-            .filter(|r| seen_paths.insert(r.path.clone()))
-            .take(limit)
+            .filter(|r| seen_paths.insert((r.path.clone(), r.line)))
             .collect();
+        if let Some(facets) = facets {
+            deduplicated_results.iter().for_each(|result| facets.add(result));
+        }
 
-        Ok(deduplicated_results)
+        // Step 7: Re-order by the requested sort mode (stable, so ties keep score order)
+        filters.sort.apply(&mut deduplicated_results);
+
+        // Step 8: Record which filename characters matched, for highlighting in the UI
+        Ok(Some(deduplicated_results
+            .into_iter()
+            .take(limit)
+            .map(|mut result| {
+                result.match_positions = filename_match_positions(&name_parts, &result.file_name);
+                if result.match_positions.is_empty() {
+                    // Matched through a synonym; highlight that instead
+                    result.match_positions = synonym_variants
+                        .iter()
+                        .map(|variant| {
+                            let variant: Vec<&str> = variant.iter().map(String::as_str).collect();
+                            filename_match_positions(&variant, &result.file_name)
+                        })
+                        .find(|positions| !positions.is_empty())
+                        .unwrap_or_default();
+                }
+                result
+            })
+            .collect()))
     }
 
-    /// Calculates a launcher-style match score
-    /// Returns Some(score) if the query matches, None otherwise
-    /// 
-    /// Matching rules:
-    /// - For "7r" searching "7 Rules": chars must appear in order
-    /// - For "7 rules" searching "7 Rules of Power": both parts must match
-    fn calculate_launcher_score(query_parts: &[&str], file_name: &str) -> Option<f32> {
-        if query_parts.is_empty() {
-            return None;
-        }
+    /// Finds indexed files, bookmarks and history entries similar to an indexed document
+    ///
+    /// Builds a `MoreLikeThisQuery` from the document's most distinctive content and
+    /// name terms. Content isn't stored, so a text file is re-read from disk; binary
+    /// files and browser records fall back to their file name or title. The document
+    /// itself is left out of the results.
+    pub fn related(&self, path: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
+        let index = self.get_index()?;
+        let searcher = index.reader()?.searcher();
 
-        let mut total_score = 0.0f32;
-        
-        // 1. Check for exact/prefix/substring matches of the full query (if single part)
-        // This gives a huge boost to "vibe" matching "vibe_coding"
-        if query_parts.len() == 1 {
-            let part = query_parts[0];
-            if file_name.starts_with(part) {
-                total_score += 1000.0;
-            } else if file_name.contains(part) {
-                total_score += 500.0;
+        let Some((_, address)) = searcher.search(&self.record_query(path), &TopDocs::with_limit(1))?.into_iter().next() else {
+            return Err(tantivy::TantivyError::InvalidArgument(format!("{} is not indexed", path)));
+        };
+        let source = self.doc_to_result(&searcher.doc(address)?, 0.0);
+        let content = if source.record_type == "file" && self.is_text_indexable(&source.extension) {
+            self.read_file_content(Path::new(path)).unwrap_or_default()
+        } else {
+            source.file_name.clone()
+        };
+
+        let query = MoreLikeThisQuery::builder()
+            .with_min_doc_frequency(1)
+            .with_min_term_frequency(1)
+            .with_min_word_length(2)
+            .with_max_query_terms(RELATED_MAX_QUERY_TERMS)
+            .with_document_fields(vec![
+                (self.content_field, vec![OwnedValue::from(content)]),
+                (self.file_name_field, vec![OwnedValue::from(source.file_name)]),
+            ]);
+
+        let mut results = Vec::new();
+        for (score, doc_address) in searcher.search(&query, &TopDocs::with_limit(limit + 1))? {
+            let doc: TantivyDocument = searcher.doc(doc_address)?;
+            let result = self.doc_to_result(&doc, score);
+            if result.path != path {
+                results.push(result);
             }
         }
+        results.truncate(limit);
+        Ok(results)
+    }
 
-        let mut search_pos = 0usize;
-        let file_chars: Vec<char> = file_name.chars().collect();
+    /// "Did you mean" suggestions for a query that found nothing
+    ///
+    /// Each query word's Levenshtein automaton walks the content and filename term
+    /// dictionaries, visiting only terms within `max_typo_distance` of it. Words found in
+    /// the index are kept; others are replaced by the closest terms, by edit distance and
+    /// then by how many documents contain them. Filter operators (`ext:pdf`, `under:work`)
+    /// are kept as typed. Returns whole queries, best first.
+    pub fn suggest_spelling(&self, query: &str, limit: usize) -> tantivy::Result<Vec<String>> {
+        let parts: Vec<&str> = query.split_whitespace().collect();
+        // Only plain query words are corrected, not filter operators
+        let words: Vec<Option<String>> = parts
+            .iter()
+            .map(|part| (parse_search_query(part).query == *part).then(|| part.to_lowercase()))
+            .collect();
+        if limit == 0 || words.iter().all(Option::is_none) {
+            return Ok(Vec::new());
+        }
 
-        for part in query_parts {
-            if part.is_empty() {
+        // Close dictionary terms per word, with their distance and summed doc frequency
+        let mut candidates: Vec<std::collections::HashMap<String, (usize, u64)>> =
+            vec![std::collections::HashMap::new(); words.len()];
+        let searcher = self.get_index()?.reader()?.searcher();
+        for (word, word_candidates) in words.iter().zip(candidates.iter_mut()) {
+            let Some(automaton) = word.as_deref().and_then(TypoAutomaton::for_word) else {
+                continue;
+            };
+            for segment_reader in searcher.segment_readers() {
+                for field in [self.content_field, self.file_name_field] {
+                    let inverted_index = segment_reader.inverted_index(field)?;
+                    let mut terms = inverted_index.terms().search(&automaton).into_stream()?;
+                    while terms.advance() {
+                        let Ok(term) = std::str::from_utf8(terms.key()) else {
+                            continue;
+                        };
+                        let Some(distance) = automaton.distance(term) else {
+                            continue;
+                        };
+                        let entry = word_candidates.entry(term.to_string()).or_insert((distance, 0));
+                        entry.1 += terms.value().doc_freq as u64;
+                    }
+                }
+            }
+        }
+
+        let ranked: Vec<Vec<String>> = candidates
+            .into_iter()
+            .zip(&words)
+            .map(|(word_candidates, word)| {
+                // A word the index already knows is left alone
+                if word.as_ref().is_some_and(|word| word_candidates.get(word).is_some_and(|(d, _)| *d == 0)) {
+                    return Vec::new();
+                }
+                let mut ranked: Vec<(String, (usize, u64))> = word_candidates.into_iter().collect();
+                ranked.sort_by(|(a_term, (a_dist, a_freq)), (b_term, (b_dist, b_freq))| {
+                    a_dist.cmp(b_dist).then(b_freq.cmp(a_freq)).then(a_term.cmp(b_term))
+                });
+                ranked.into_iter().map(|(term, _)| term).take(limit).collect()
+            })
+            .collect();
+
+        // The n-th suggestion uses each word's n-th best correction (or its best one)
+        let mut suggestions = Vec::new();
+        let alternatives = ranked.iter().map(Vec::len).max().unwrap_or(0);
+        for n in 0..alternatives {
+            let suggestion = parts
+                .iter()
+                .zip(&ranked)
+                .map(|(part, corrections)| match corrections.get(n).or(corrections.first()) {
+                    Some(correction) => correction.as_str(),
+                    None => part,
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            if !suggestions.contains(&suggestion) {
+                suggestions.push(suggestion);
+            }
+        }
+        suggestions.truncate(limit);
+        Ok(suggestions)
+    }
+
+    /// Index terms that complete a prefix, from file names and from content
+    ///
+    /// Terms are ranked by how many documents contain them. The index stores runs of
+    /// Chinese text as single terms, so for a Chinese prefix the runs containing it are
+    /// cut into words with jieba and the words starting with the prefix are offered.
+    pub fn complete_prefix(&self, prefix: &str, limit: usize) -> tantivy::Result<TermCompletions> {
+        let prefix = prefix.trim().to_lowercase();
+        if limit == 0 || (!Self::contains_chinese(&prefix) && prefix.chars().count() < MIN_COMPLETION_PREFIX_LEN) {
+            return Ok(TermCompletions::default());
+        }
+
+        let searcher = self.get_index()?.reader()?.searcher();
+        let (file_name_field, content_field) = if Self::contains_chinese(&prefix) {
+            (self.file_name_zh_field, self.content_zh_field)
+        } else {
+            (self.file_name_field, self.content_field)
+        };
+        Ok(TermCompletions {
+            filename_terms: self.field_completions(&searcher, file_name_field, &prefix, limit)?,
+            content_terms: self.field_completions(&searcher, content_field, &prefix, limit)?,
+        })
+    }
+
+    /// Completions of a lowercase prefix from one field's term dictionary, most frequent first
+    fn field_completions(&self, searcher: &tantivy::Searcher, field: Field, prefix: &str, limit: usize) -> tantivy::Result<Vec<String>> {
+        let mut doc_freqs: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
+
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(field)?;
+            let mut terms = inverted_index.terms().range().ge(prefix.as_bytes()).into_stream()?;
+            while terms.advance() {
+                let Ok(term) = std::str::from_utf8(terms.key()) else {
+                    continue;
+                };
+                if !term.starts_with(prefix) {
+                    break; // Terms are sorted, so no later term has the prefix
+                } else if term.len() > prefix.len() {
+                    *doc_freqs.entry(term.to_string()).or_default() += terms.value().doc_freq as u64;
+                }
+            }
+        }
+
+        let mut ranked: Vec<(String, u64)> = doc_freqs.into_iter().collect();
+        ranked.sort_by(|(a_term, a_freq), (b_term, b_freq)| b_freq.cmp(a_freq).then(a_term.cmp(b_term)));
+        Ok(ranked.into_iter().take(limit).map(|(term, _)| term).collect())
+    }
+
+    /// Finds groups of indexed files with identical content, largest wasted space first
+    ///
+    /// Uses the size and content hash stored at index time. Files above 1 MB are indexed
+    /// with a sampled hash (size plus both ends), so their groups are confirmed by hashing
+    /// each copy in full before they are reported.
+    pub fn find_duplicates(&self) -> tantivy::Result<Vec<DuplicateGroup>> {
+        let searcher = self.get_index()?.reader()?.searcher();
+        let doc_groups = self.duplicate_doc_groups(&searcher)?;
+        let mut groups = self.duplicate_groups_with_paths(&searcher, &doc_groups)?;
+        groups.sort_by(|a, b| {
+            let wasted = |g: &DuplicateGroup| g.size.saturating_mul(g.paths.len() as u64 - 1);
+            wasted(b).cmp(&wasted(a)).then_with(|| a.paths.cmp(&b.paths))
+        });
+        Ok(groups)
+    }
+
+    /// Groups live file documents by (content hash, size), keeping groups with several documents
+    ///
+    /// Reads only the fast fields; empty and unreadable files (hash 0) are never grouped.
+    fn duplicate_doc_groups(&self, searcher: &tantivy::Searcher) -> tantivy::Result<Vec<Vec<DocAddress>>> {
+        let hash_field = self.schema.get_field_name(self.content_hash_field);
+        let size_field = self.schema.get_field_name(self.size_field);
+        let mut by_content: std::collections::HashMap<(u64, u64), Vec<DocAddress>> = std::collections::HashMap::new();
+
+        for (segment_ord, segment_reader) in searcher.segment_readers().iter().enumerate() {
+            let hashes = segment_reader.fast_fields().u64(hash_field)?;
+            let sizes = segment_reader.fast_fields().u64(size_field)?;
+            for doc_id in segment_reader.doc_ids_alive() {
+                let (Some(hash), Some(size)) = (hashes.first(doc_id), sizes.first(doc_id)) else {
+                    continue;
+                };
+                if hash != 0 && size > 0 {
+                    by_content.entry((hash, size)).or_default().push(DocAddress::new(segment_ord as u32, doc_id));
+                }
+            }
+        }
+
+        Ok(by_content.into_values().filter(|docs| docs.len() > 1).collect())
+    }
+
+    /// Loads the distinct paths of `duplicate_doc_groups`, keeping groups that are still
+    /// duplicates (split by full content above `HASH_FULL_LIMIT`)
+    fn duplicate_groups_with_paths(&self, searcher: &tantivy::Searcher, doc_groups: &[Vec<DocAddress>]) -> tantivy::Result<Vec<DuplicateGroup>> {
+        let mut groups = Vec::new();
+        for docs in doc_groups {
+            let mut paths = Vec::with_capacity(docs.len());
+            let mut content = None;
+            for &address in docs {
+                let doc: TantivyDocument = searcher.doc(address)?;
+                let Some(path) = doc.get_first(self.path_field).and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                paths.push(path.to_string());
+                content = content.or_else(|| {
+                    let hash = doc.get_first(self.content_hash_field).and_then(|v| v.as_u64())?;
+                    let size = doc.get_first(self.size_field).and_then(|v| v.as_u64())?;
+                    Some((hash, size))
+                });
+            }
+            paths.sort();
+            paths.dedup();
+            let Some((hash, size)) = content else {
+                continue;
+            };
+            if size <= HASH_FULL_LIMIT {
+                if paths.len() > 1 {
+                    groups.push(DuplicateGroup { hash: format!("{:016x}", hash), size, paths });
+                }
+                continue;
+            }
+
+            // Sampled hashes only make a candidate group; split it by full content
+            let mut by_full_hash: std::collections::BTreeMap<u64, Vec<String>> = std::collections::BTreeMap::new();
+            for path in paths {
+                if let Ok(full_hash) = full_content_hash_cached(Path::new(&path)) {
+                    by_full_hash.entry(full_hash).or_default().push(path);
+                }
+            }
+            groups.extend(
+                by_full_hash
+                    .into_iter()
+                    .filter(|(_, paths)| paths.len() > 1)
+                    .map(|(full_hash, paths)| DuplicateGroup { hash: format!("{:016x}", full_hash), size, paths }),
+            );
+        }
+        Ok(groups)
+    }
+
+    /// Builds a range query over the `size` and `modified_time` fast fields
+    /// Returns None if the filters contain no size/date bounds
+    fn build_range_filter_query(&self, filters: &SearchFilters) -> Option<BooleanQuery> {
+        if !filters.has_range_filters() {
+            return None;
+        }
+
+        let to_bound_u64 = |v: Option<u64>| v.map(Bound::Included).unwrap_or(Bound::Unbounded);
+        let to_bound_i64 = |v: Option<i64>| v.map(Bound::Included).unwrap_or(Bound::Unbounded);
+
+        // Browser records carry size 0 / mtime 0, so range filters only apply to files
+        let file_term = Term::from_field_text(self.record_type_field, "file");
+        let mut subqueries: Vec<(Occur, Box<dyn Query>)> = vec![
+            (Occur::Must, Box::new(TermQuery::new(file_term, IndexRecordOption::Basic))),
+        ];
+
+        if filters.min_size.is_some() || filters.max_size.is_some() {
+            subqueries.push((Occur::Must, Box::new(RangeQuery::new_u64_bounds(
+                self.schema.get_field_name(self.size_field).to_string(),
+                to_bound_u64(filters.min_size),
+                to_bound_u64(filters.max_size),
+            ))));
+        }
+
+        if filters.modified_after.is_some() || filters.modified_before.is_some() {
+            subqueries.push((Occur::Must, Box::new(RangeQuery::new_i64_bounds(
+                self.schema.get_field_name(self.modified_time_field).to_string(),
+                to_bound_i64(filters.modified_after),
+                to_bound_i64(filters.modified_before),
+            ))));
+        }
+
+        Some(BooleanQuery::new(subqueries))
+    }
+
+    /// Calculates a launcher-style match score
+    /// Returns Some(score) if the query matches, None otherwise
+    /// 
+    /// Matching rules:
+    /// - For "7r" searching "7 Rules": chars must appear in order
+    /// - For "7 rules" searching "7 Rules of Power": both parts must match
+    ///
+    /// Query parts are lowercase; `file_name` keeps its case so that case changes
+    /// ("getUserName") count as word boundaries.
+    fn calculate_launcher_score(query_parts: &[&str], file_name: &str) -> Option<f32> {
+        if query_parts.is_empty() {
+            return None;
+        }
+        let original_chars: Vec<(usize, char)> = file_name.char_indices().collect();
+        let file_name = &file_name.to_lowercase();
+
+        let mut total_score = 0.0f32;
+        
+        // 1. Check for exact/prefix/substring matches of the full query (if single part)
+        // This gives a huge boost to "vibe" matching "vibe_coding"
+        if query_parts.len() == 1 {
+            let part = query_parts[0];
+            if file_name.starts_with(part) {
+                total_score += 1000.0;
+            } else if file_name.contains(part) {
+                total_score += 500.0;
+            }
+        }
+
+        let mut search_pos = 0usize;
+        let file_chars: Vec<char> = original_chars.iter().map(|&(_, c)| c.to_lowercase().next().unwrap_or(c)).collect();
+
+        for part in query_parts {
+            if part.is_empty() {
                 continue;
             }
 
@@ -674,7 +1606,10 @@ impl TantivyEngine {
                     }
                     
                     // Bonus for matching at word boundaries
-                    if search_pos == 0 || !file_chars[search_pos - 1].is_alphanumeric() {
+                    if search_pos == 0
+                        || !file_chars[search_pos - 1].is_alphanumeric()
+                        || is_case_boundary(&original_chars, search_pos)
+                    {
                         total_score += 10.0; // Significant word boundary bonus
                     }
                     
@@ -735,6 +1670,42 @@ impl TantivyEngine {
         Some(total_score)
     }
 
+    /// Scores query parts that contain path separators (like "proj/src") against a path
+    /// Returns Some(score) if every segment matches a path component in order, None otherwise
+    ///
+    /// Each segment must be contained in a component; prefix and exact component
+    /// matches score higher, as do segments that match adjacent components.
+    fn calculate_path_score(path_parts: &[&str], path: &str) -> Option<f32> {
+        let components: Vec<&str> = split_path_components(path);
+        let mut total_score = 0.0f32;
+        let mut search_pos = 0usize;
+
+        for part in path_parts {
+            let mut previous_match: Option<usize> = None;
+            for segment in split_path_components(part) {
+                let offset = components[search_pos..].iter().position(|c| c.contains(segment))?;
+                let matched = search_pos + offset;
+                let component = components[matched];
+
+                total_score += if component == segment {
+                    50.0
+                } else if component.starts_with(segment) {
+                    30.0
+                } else {
+                    10.0
+                };
+                if previous_match.is_some_and(|p| p + 1 == matched) {
+                    total_score += 10.0; // Adjacent components, like "proj/src" in ".../proj/src/..."
+                }
+
+                previous_match = Some(matched);
+                search_pos = matched + 1;
+            }
+        }
+
+        Some(total_score)
+    }
+
     /// Tokenizes a query string, handling both English and Chinese text
     fn tokenize_query(&self, query: &str) -> Vec<String> {
         let mut tokens = Vec::new();
@@ -742,8 +1713,7 @@ impl TantivyEngine {
         // Check if query contains Chinese characters
         if Self::contains_chinese(query) {
             // Use jieba for Chinese tokenization
-            let jieba = Jieba::new();
-            let words = jieba.cut(query, true); // Use search mode
+            let words = JIEBA.cut(query, true); // Use search mode
             for word in words {
                 let trimmed = word.trim();
                 if !trimmed.is_empty() && trimmed.len() > 0 {
@@ -780,7 +1750,7 @@ impl TantivyEngine {
     /// Deletes a specific file from the index
     pub fn delete_file(&self, path: &str) -> tantivy::Result<()> {
         let index = self.get_index()?;
-        let mut writer: IndexWriter<TantivyDocument> = index.writer(50_000_000)?;
+        let mut writer = self.writer(&index)?;
         let term = Term::from_field_text(self.path_field, path);
         writer.delete_term(term);
         writer.commit()?;
@@ -794,15 +1764,18 @@ impl TantivyEngine {
         let searcher = reader.searcher();
         
         // Find all documents with paths starting with this folder
-        let mut writer: IndexWriter<TantivyDocument> = index.writer(50_000_000)?;
+        let mut writer = self.writer(&index)?;
         let mut deleted_count = 0u32;
         
         // We need to iterate through all documents and delete those matching the folder
         // This is less efficient but more reliable than trying to use prefix queries
         for segment_reader in searcher.segment_readers() {
             let store_reader = segment_reader.get_store_reader(1)?;
-            for doc_id in 0..segment_reader.num_docs() {
+            for doc_id in segment_reader.doc_ids_alive() {
                 if let Ok(doc) = store_reader.get::<TantivyDocument>(doc_id) {
+                    if self.is_symbol_doc(&doc) {
+                        continue;
+                    }
                     for field_value in doc.field_values() {
                         if field_value.field() == self.path_field {
                             if let Some(path) = field_value.value().as_str() {
@@ -824,12 +1797,288 @@ impl TantivyEngine {
 
     /// Clears the entire index
     pub fn clear_index(&self) -> tantivy::Result<()> {
+        // A job's uncommitted writes would be lost with the index anyway
+        self.job_writer.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?.take();
         if self.index_path.exists() {
             fs::remove_dir_all(&self.index_path)?;
         }
         Ok(())
     }
 
+    /// Directory a rebuild is written to before it replaces the live index
+    fn shadow_index_path(&self) -> PathBuf {
+        let mut name = self.index_path.file_name().unwrap_or_default().to_os_string();
+        name.push(".shadow");
+        self.index_path.with_file_name(name)
+    }
+
+    /// Creates an engine over an empty shadow index for a rebuild
+    ///
+    /// Index into the returned engine, then call `swap_in_shadow` once it is complete.
+    /// Until then the live index is untouched and keeps serving searches.
+    pub fn create_shadow(&self) -> tantivy::Result<TantivyEngine> {
+        let shadow_path = self.shadow_index_path();
+        // Leftover from an interrupted rebuild
+        if shadow_path.exists() {
+            fs::remove_dir_all(&shadow_path)?;
+        }
+        let mut shadow = TantivyEngine::new_with_path(shadow_path)?;
+        shadow.set_exclusions(self.exclusions.clone());
+        shadow.get_index()?;
+        Ok(shadow)
+    }
+
+    /// Replaces the live index with a completed shadow index
+    ///
+    /// The live index is moved aside before the shadow is moved into its place and is
+    /// only deleted once that succeeded; if the swap fails it is restored.
+    pub fn swap_in_shadow(&self, shadow: &TantivyEngine) -> tantivy::Result<()> {
+        // A job's writer still open on the live index would commit into the moved directory
+        self.commit_job_writes()?;
+        // Make sure the shadow holds a valid index, even if nothing was indexed
+        shadow.commit_job_writes()?;
+        shadow.get_index()?;
+
+        let mut backup_name = self.index_path.file_name().unwrap_or_default().to_os_string();
+        backup_name.push(".old");
+        let backup_path = self.index_path.with_file_name(backup_name);
+        if backup_path.exists() {
+            fs::remove_dir_all(&backup_path)?;
+        }
+
+        let had_live_index = self.index_path.exists();
+        if had_live_index {
+            fs::rename(&self.index_path, &backup_path)?;
+        }
+        if let Err(e) = fs::rename(&shadow.index_path, &self.index_path) {
+            if had_live_index {
+                fs::rename(&backup_path, &self.index_path)?;
+            }
+            return Err(e.into());
+        }
+        if had_live_index {
+            // The new index is already live; a leftover backup is cleaned up next time
+            let _ = fs::remove_dir_all(&backup_path);
+        }
+        Ok(())
+    }
+
+    /// Writes the index and a manifest (schema version, roots, usage data) into a single tar archive
+    ///
+    /// The archive can be imported on another machine with `load_snapshot`, which saves
+    /// re-reading every file there.
+    pub fn export_snapshot(&self, archive_path: &Path, roots: &[String], recent_queries: &[String]) -> tantivy::Result<SnapshotManifest> {
+        let index = self.get_index()?;
+        let manifest = SnapshotManifest {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            schema_version: SCHEMA_VERSION,
+            created_at: chrono::Utc::now().timestamp(),
+            roots: roots.to_vec(),
+            document_count: self.count_records(&index.reader()?.searcher())?,
+            recent_queries: recent_queries.to_vec(),
+            crawl_times: self.crawl_times(),
+        };
+        let manifest_json = serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+
+        let mut builder = tar::Builder::new(fs::File::create(archive_path)?);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest_json.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, SNAPSHOT_MANIFEST, manifest_json.as_slice())?;
+
+        for entry in fs::read_dir(&self.index_path)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            // Lock files belong to the running app, not to the index
+            if !entry.file_type()?.is_file() || name.to_string_lossy().starts_with(".tantivy-") {
+                continue;
+            }
+            builder.append_path_with_name(entry.path(), Path::new(SNAPSHOT_INDEX_DIR).join(&name))?;
+        }
+        builder.into_inner()?.sync_all()?;
+
+        Ok(manifest)
+    }
+
+    /// Replaces this engine's index with the one in a snapshot archive
+    ///
+    /// Meant for a shadow engine (see `create_shadow`), so a bad archive never touches
+    /// the live index. With `rebase`, file paths under the first prefix are moved under
+    /// the second (e.g. `D:\Data` to `/mnt/data`).
+    pub fn load_snapshot(&self, archive_path: &Path, rebase: Option<(&str, &str)>) -> tantivy::Result<SnapshotManifest> {
+        let invalid = |message: &str| tantivy::TantivyError::InvalidArgument(format!("Invalid snapshot: {}", message));
+
+        self.clear_index()?;
+        fs::create_dir_all(&self.index_path)?;
+
+        let mut manifest: Option<SnapshotManifest> = None;
+        let mut archive = tar::Archive::new(fs::File::open(archive_path)?);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let entry_path = entry.path()?.into_owned();
+            if entry_path == Path::new(SNAPSHOT_MANIFEST) {
+                manifest = Some(serde_json::from_reader(&mut entry).map_err(|e| invalid(&e.to_string()))?);
+            } else if let Ok(file_name) = entry_path.strip_prefix(SNAPSHOT_INDEX_DIR) {
+                // Only plain file names, so a crafted archive can't write outside the index
+                let mut components = file_name.components();
+                match (components.next(), components.next()) {
+                    (Some(std::path::Component::Normal(name)), None) => {
+                        entry.unpack(self.index_path.join(name))?;
+                    }
+                    _ => return Err(invalid("unexpected entry")),
+                }
+            }
+        }
+
+        let mut manifest = manifest.ok_or_else(|| invalid("missing manifest"))?;
+        if manifest.schema_version != SCHEMA_VERSION {
+            return Err(invalid(&format!(
+                "schema version {} doesn't match this version's {}",
+                manifest.schema_version, SCHEMA_VERSION
+            )));
+        }
+        let index = Index::open_in_dir(&self.index_path)?;
+        if index.schema() != self.schema {
+            return Err(invalid("schema doesn't match"));
+        }
+        register_tokenizers(&index);
+
+        if let Some((from, to)) = rebase {
+            self.rebase_paths(&index, from, to)?;
+            for root in manifest.roots.iter_mut() {
+                if let Some(rebased) = rebase_path(root, from, to) {
+                    *root = rebased;
+                }
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Moves indexed files from one root prefix to another
+    ///
+    /// Only the path, name and type are carried over. Content isn't stored, and the
+    /// size, times and hash describe the other machine's copy, so a rebased file has no
+    /// indexed metadata; the Import job that follows reads it from its new location like
+    /// a new file. Until then it is only found by name.
+    fn rebase_paths(&self, index: &Index, from: &str, to: &str) -> tantivy::Result<()> {
+        let searcher = index.reader()?.searcher();
+        let mut writer = self.writer(index)?;
+        let carried_over = [self.extension_field, self.url_field, self.record_type_field];
+
+        for segment_reader in searcher.segment_readers() {
+            let store_reader = segment_reader.get_store_reader(1)?;
+            for doc_id in segment_reader.doc_ids_alive() {
+                let doc: TantivyDocument = store_reader.get(doc_id)?;
+                if doc.get_first(self.record_type_field).and_then(|v| v.as_str()) != Some("file") {
+                    continue;
+                }
+                let Some(old_path) = doc.get_first(self.path_field).and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let Some(new_path) = rebase_path(old_path, from, to) else {
+                    continue;
+                };
+
+                let mut rebased = TantivyDocument::new();
+                rebased.add_text(self.path_field, &new_path);
+                let file_name = doc.get_first(self.file_name_field).and_then(|v| v.as_str()).unwrap_or_default();
+                self.add_file_name(&mut rebased, file_name);
+                for field_value in doc.field_values() {
+                    if carried_over.contains(&field_value.field()) {
+                        rebased.add_field_value(field_value.field(), field_value.value().clone());
+                    }
+                }
+
+                // Deleting the old path drops the file's symbols as well; the Import job re-adds them
+                writer.delete_term(Term::from_field_text(self.path_field, old_path));
+                writer.add_document(rebased)?;
+            }
+        }
+
+        writer.commit()?;
+        Ok(())
+    }
+
+    /// Merges all segments into one, dropping deleted documents, then removes unused files
+    ///
+    /// Every small commit (e.g. from the file watcher) leaves a segment behind; this
+    /// compacts them so searches touch fewer segments.
+    pub fn merge_segments(&self) -> tantivy::Result<MaintenanceResult> {
+        let index = self.get_index()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        let segments_before = segment_ids.len() as u64;
+
+        let mut writer = self.writer(&index)?;
+        if segment_ids.len() > 1 {
+            writer.merge(&segment_ids).wait()?;
+        }
+        let gc_result = writer.garbage_collect_files().wait()?;
+        writer.wait_merging_threads()?;
+
+        Ok(MaintenanceResult {
+            segments_before,
+            segments_after: index.searchable_segment_ids()?.len() as u64,
+            files_removed: gc_result.deleted_files.len() as u64,
+        })
+    }
+
+    /// Deletes index files no longer used by any segment (e.g. left over from merges)
+    pub fn garbage_collect(&self) -> tantivy::Result<MaintenanceResult> {
+        let index = self.get_index()?;
+        let segments = index.searchable_segment_ids()?.len() as u64;
+
+        let writer = self.writer(&index)?;
+        let gc_result = writer.garbage_collect_files().wait()?;
+
+        Ok(MaintenanceResult {
+            segments_before: segments,
+            segments_after: segments,
+            files_removed: gc_result.deleted_files.len() as u64,
+        })
+    }
+
+    /// Checks that every indexed file path is unique and lies under one of the roots
+    ///
+    /// Browser records are keyed by URL and only checked for uniqueness.
+    pub fn verify_index(&self, roots: &[String]) -> tantivy::Result<IndexVerification> {
+        let searcher = self.get_index()?.reader()?.searcher();
+        let roots: Vec<&Path> = roots.iter().map(Path::new).collect();
+
+        let mut seen_paths = std::collections::HashSet::new();
+        let mut verification = IndexVerification::default();
+        for segment_reader in searcher.segment_readers() {
+            let store_reader = segment_reader.get_store_reader(1)?;
+            for doc_id in segment_reader.doc_ids_alive() {
+                let doc: TantivyDocument = store_reader.get(doc_id)?;
+                // Symbols share their file's path
+                if self.is_symbol_doc(&doc) {
+                    continue;
+                }
+                verification.documents_checked += 1;
+                let path = doc.get_first(self.path_field).and_then(|v| v.as_str()).unwrap_or_default();
+                let record_type = doc.get_first(self.record_type_field).and_then(|v| v.as_str()).unwrap_or_default();
+
+                if !seen_paths.insert(path.to_string()) {
+                    verification.duplicate_path_count += 1;
+                    if verification.duplicate_paths.len() < MAX_VERIFY_SAMPLES {
+                        verification.duplicate_paths.push(path.to_string());
+                    }
+                }
+                if record_type == "file" && !roots.iter().any(|root| Path::new(path).starts_with(root)) {
+                    verification.outside_root_count += 1;
+                    if verification.paths_outside_roots.len() < MAX_VERIFY_SAMPLES {
+                        verification.paths_outside_roots.push(path.to_string());
+                    }
+                }
+            }
+        }
+
+        verification.is_healthy = verification.duplicate_path_count == 0 && verification.outside_root_count == 0;
+        Ok(verification)
+    }
+
     /// Gets the index path
     pub fn get_index_path(&self) -> &std::path::PathBuf {
         &self.index_path
@@ -840,11 +2089,21 @@ impl TantivyEngine {
         let index = self.get_index()?;
         let reader = index.reader()?;
         let searcher = reader.searcher();
-        Ok(searcher.num_docs())
+        self.count_records(&searcher)
+    }
+
+    /// Counts files, bookmarks and history entries, leaving out the files' symbol records
+    fn count_records(&self, searcher: &tantivy::Searcher) -> tantivy::Result<u64> {
+        let symbol_term = Term::from_field_text(self.record_type_field, SYMBOL_RECORD_TYPE);
+        let symbols = searcher.search(&TermQuery::new(symbol_term, IndexRecordOption::Basic), &tantivy::collector::Count)?;
+        Ok(searcher.num_docs() - symbols as u64)
     }
 
-    /// Gets index statistics
-    pub fn get_index_stats(&self) -> tantivy::Result<IndexStats> {
+    /// Gets index statistics, with document counts and sizes per root folder
+    ///
+    /// Files are attributed to the most specific root containing them; crawl times and
+    /// the watcher state aren't known to the engine and are left for the caller to fill in.
+    pub fn get_index_stats(&self, roots: &[String]) -> tantivy::Result<IndexStats> {
         let doc_count = self.get_document_count()?;
         let size_bytes = self.calculate_index_size();
         
@@ -865,6 +2124,51 @@ impl TantivyEngine {
         // Count files explicitly (for backward compatibility and accuracy)
         let file_count = count_by_type("file");
 
+        // Segment health: many small segments or deleted docs mean a merge is worthwhile
+        let segment_count = searcher.segment_readers().len() as u64;
+        let deleted_doc_count = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment_reader| segment_reader.num_deleted_docs() as u64)
+            .sum();
+
+        let mut folders: Vec<FolderStats> = roots
+            .iter()
+            .map(|root| FolderStats { path: root.clone(), document_count: 0, size_bytes: 0 })
+            .collect();
+        let mut extensions: std::collections::HashMap<String, ExtensionStats> = std::collections::HashMap::new();
+        let mut failed_file_count = 0;
+        let file_query = TermQuery::new(Term::from_field_text(self.record_type_field, "file"), IndexRecordOption::Basic);
+        for address in searcher.search(&file_query, &DocSetCollector)? {
+            let doc: TantivyDocument = searcher.doc(address)?;
+            let path = doc.get_first(self.path_field).and_then(|v| v.as_str()).unwrap_or_default();
+            let size = doc.get_first(self.size_field).and_then(|v| v.as_u64()).unwrap_or(0);
+            let extension = doc.get_first(self.extension_field).and_then(|v| v.as_str()).unwrap_or_default();
+
+            let root = folders
+                .iter_mut()
+                .filter(|folder| Path::new(path).starts_with(&folder.path))
+                .max_by_key(|folder| folder.path.len());
+            if let Some(root) = root {
+                root.document_count += 1;
+                root.size_bytes += size;
+            }
+            let extension_stats = extensions.entry(extension.to_string()).or_insert_with(|| ExtensionStats {
+                extension: extension.to_string(),
+                document_count: 0,
+                size_bytes: 0,
+            });
+            extension_stats.document_count += 1;
+            extension_stats.size_bytes += size;
+            if doc.get_first(self.index_error_field).is_some() {
+                failed_file_count += 1;
+            }
+        }
+        let mut top_extensions: Vec<ExtensionStats> = extensions.into_values().collect();
+        top_extensions.sort_by(|a, b| b.document_count.cmp(&a.document_count).then_with(|| a.extension.cmp(&b.extension)));
+        top_extensions.truncate(MAX_STATS_EXTENSIONS);
+        let crawl_times = self.crawl_times();
+
         Ok(IndexStats {
             document_count: doc_count,
             size_bytes,
@@ -872,9 +2176,56 @@ impl TantivyEngine {
             file_count: Some(file_count),
             bookmark_count: Some(bookmark_count),
             history_count: Some(history_count),
+            segment_count,
+            deleted_doc_count,
+            folders,
+            top_extensions,
+            failed_file_count,
+            last_crawl_started: crawl_times.started,
+            last_indexed: crawl_times.completed,
+            watcher_running: false,
+            watched_folders: Vec::new(),
         })
     }
 
+    /// When the last crawl of the folders started and when the last one completed
+    pub fn crawl_times(&self) -> CrawlTimes {
+        fs::read(self.crawl_times_path())
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Records that a crawl started (`completed` false) or completed, so it survives restarts
+    pub fn record_crawl(&self, completed: bool) -> tantivy::Result<()> {
+        let mut times = self.crawl_times();
+        let now = Some(chrono::Utc::now().timestamp());
+        if completed {
+            times.completed = now;
+        } else {
+            times.started = now;
+        }
+        self.set_crawl_times(times)
+    }
+
+    /// Replaces the recorded crawl times, e.g. with those of an imported snapshot
+    pub fn set_crawl_times(&self, times: CrawlTimes) -> tantivy::Result<()> {
+        let json = serde_json::to_vec(&times).map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+        let path = self.crawl_times_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Kept beside the index directory rather than in it, so rebuilds and snapshots leave it alone
+    fn crawl_times_path(&self) -> PathBuf {
+        let mut name = self.index_path.file_name().unwrap_or_default().to_os_string();
+        name.push(".crawl.json");
+        self.index_path.with_file_name(name)
+    }
+
     /// Calculates the total size of the index directory
     fn calculate_index_size(&self) -> u64 {
         if !self.index_path.exists() {
@@ -891,6 +2242,40 @@ impl TantivyEngine {
     }
 }
 
+/// Index terms completing a prefix, per source, most frequent first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TermCompletions {
+    pub filename_terms: Vec<String>,
+    pub content_terms: Vec<String>,
+}
+
+/// Files in the index that share the same content
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    /// Content hash as hex (a u64 would lose precision in JavaScript)
+    pub hash: String,
+    /// Size of each copy in bytes
+    pub size: u64,
+    /// Paths of all copies, sorted
+    pub paths: Vec<String>,
+}
+
+/// Stored metadata used to decide whether a file changed since it was indexed
+struct IndexedFileMeta {
+    modified_time: i64,
+    size: u64,
+    content_hash: u64,
+    /// When the content was read; None for documents from before `indexed_time` existed
+    indexed_time: Option<i64>,
+}
+
+/// How an indexing pass changed the index
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexChanges {
+    pub added: u32,
+    pub updated: u32,
+}
+
 /// Statistics about the search index
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct IndexStats {
@@ -900,6 +2285,160 @@ pub struct IndexStats {
     pub file_count: Option<u64>,
     pub bookmark_count: Option<u64>,
     pub history_count: Option<u64>,
+    pub segment_count: u64,
+    /// Deleted documents still taking up space until their segment is merged
+    pub deleted_doc_count: u64,
+    /// Indexed files and their total size per indexed folder
+    pub folders: Vec<FolderStats>,
+    /// Extensions with the most indexed files
+    pub top_extensions: Vec<ExtensionStats>,
+    /// Files indexed by name only because their content couldn't be read
+    pub failed_file_count: u64,
+    /// Unix timestamp of when the last crawl of the folders started
+    pub last_crawl_started: Option<i64>,
+    /// Unix timestamp of when the last crawl finished without being cancelled or failing
+    pub last_indexed: Option<i64>,
+    pub watcher_running: bool,
+    pub watched_folders: Vec<String>,
+}
+
+/// Indexed files under one root folder
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FolderStats {
+    pub path: String,
+    pub document_count: u64,
+    pub size_bytes: u64,
+}
+
+/// Indexed files with one extension
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionStats {
+    /// Lowercase, without the dot; empty for files without an extension
+    pub extension: String,
+    pub document_count: u64,
+    pub size_bytes: u64,
+}
+
+/// Unix timestamps of the last crawl of a workspace's folders, stored beside its index
+///
+/// Cancelled and failed crawls count as started but never as completed.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CrawlTimes {
+    pub started: Option<i64>,
+    pub completed: Option<i64>,
+}
+
+/// Describes a snapshot archive; stored in it as `manifest.json`
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotManifest {
+    pub format_version: u32,
+    pub schema_version: u32,
+    /// Unix timestamp of the export
+    pub created_at: i64,
+    /// Indexed folders at export time (rebased after an import with a rebase)
+    pub roots: Vec<String>,
+    pub document_count: u64,
+    /// Usage data, missing from snapshots of older builds
+    #[serde(default)]
+    pub recent_queries: Vec<String>,
+    #[serde(default)]
+    pub crawl_times: CrawlTimes,
+}
+
+/// Outcome of a merge or garbage collection run
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaintenanceResult {
+    pub segments_before: u64,
+    pub segments_after: u64,
+    /// Index files deleted from disk
+    pub files_removed: u64,
+}
+
+/// Findings of an index health check
+///
+/// The path lists hold at most `MAX_VERIFY_SAMPLES` entries; the counts are complete.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexVerification {
+    pub is_healthy: bool,
+    pub documents_checked: u64,
+    pub duplicate_path_count: u64,
+    pub duplicate_paths: Vec<String>,
+    pub outside_root_count: u64,
+    pub paths_outside_roots: Vec<String>,
+}
+
+/// Field that launcher results are ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortField {
+    /// Match score (best match first)
+    #[default]
+    Score,
+    /// Modified time
+    Modified,
+    /// File size in bytes
+    Size,
+    /// File name (case-insensitive)
+    Name,
+}
+
+/// Sort mode extracted from `sort:` operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortMode {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl Default for SortMode {
+    fn default() -> Self {
+        Self {
+            field: SortField::Score,
+            descending: true,
+        }
+    }
+}
+
+impl SortMode {
+    /// Parses `modified`, `size`, `name` or `score`, optionally suffixed with `-asc`/`-desc`
+    ///
+    /// Without a suffix, modified and size sort newest/largest first and name sorts A-Z.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.to_lowercase();
+        let (name, direction) = match value.rsplit_once('-') {
+            Some((name, dir @ ("asc" | "desc"))) => (name.to_string(), Some(dir == "desc")),
+            _ => (value, None),
+        };
+        let field = match name.as_str() {
+            "score" | "relevance" => SortField::Score,
+            "modified" | "date" | "mtime" => SortField::Modified,
+            "size" => SortField::Size,
+            "name" => SortField::Name,
+            _ => return None,
+        };
+        let default_descending = !matches!(field, SortField::Name);
+        Some(Self {
+            field,
+            descending: direction.unwrap_or(default_descending),
+        })
+    }
+
+    /// Re-orders results in place; score order is kept for ties
+    pub fn apply(&self, hits: &mut [SearchResult]) {
+        use std::cmp::Ordering;
+        let compare = |a: &SearchResult, b: &SearchResult| -> Ordering {
+            match self.field {
+                SortField::Score => a.score.partial_cmp(&b.score).unwrap_or(Ordering::Equal),
+                SortField::Modified => a.modified_time.cmp(&b.modified_time),
+                SortField::Size => a.size.cmp(&b.size),
+                SortField::Name => a.file_name.to_lowercase().cmp(&b.file_name.to_lowercase()),
+            }
+        };
+        match (self.field, self.descending) {
+            // Hits already arrive in descending score order
+            (SortField::Score, true) => {}
+            (_, true) => hits.sort_by(|a, b| compare(b, a)),
+            (_, false) => hits.sort_by(compare),
+        }
+    }
 }
 
 /// Search filters extracted from query
@@ -913,31 +2452,976 @@ pub struct SearchFilters {
     pub types: Vec<String>,
     /// Record types to filter (file, history, bookmark)
     pub record_types: Vec<String>,
+    /// Inclusive size bounds in bytes (from size:>10mb or size:1kb..5mb)
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    /// Inclusive modified time bounds as unix timestamps (from modified:>2024-06-01, modified:<7d)
+    pub modified_after: Option<i64>,
+    pub modified_before: Option<i64>,
+    /// Folder scopes (from path:/under:), full paths or names of indexed folders
+    pub paths: Vec<String>,
+    /// Only files whose content also exists elsewhere in the index (from dup:)
+    pub duplicates_only: bool,
+    /// Result ordering (from sort:modified, sort:size-asc, sort:name)
+    pub sort: SortMode,
+    /// Workspaces to search instead of the active one (from ws:all or ws:work,home)
+    pub workspaces: Vec<String>,
+    /// Groups of interchangeable words from the config (e.g. k8s, kubernetes)
+    pub synonyms: Vec<Vec<String>>,
+    /// Pattern matched against the lines of text files (from re: or grep:)
+    pub regex: Option<String>,
 }
 
-/// Type categories for type: filter
-pub fn get_type_extensions(type_name: &str) -> Vec<&'static str> {
-    match type_name.to_lowercase().as_str() {
-        "doc" | "document" | "documents" => vec!["pdf", "doc", "docx", "txt", "md", "rtf", "odt", "epub", "mobi"],
-        "app" | "application" | "applications" | "exe" => vec!["exe", "lnk", "app", "bat", "cmd", "msi", "dmg"],
-        "image" | "images" | "img" | "pic" => vec!["jpg", "jpeg", "png", "gif", "bmp", "svg", "webp", "ico", "tiff"],
-        "video" | "videos" | "vid" => vec!["mp4", "mkv", "avi", "mov", "wmv", "webm", "flv"],
-        "audio" | "music" | "sound" => vec!["mp3", "wav", "flac", "ogg", "aac", "m4a", "wma"],
-        "code" | "source" | "src" => vec!["rs", "py", "js", "ts", "tsx", "jsx", "java", "c", "cpp", "h", "go", "rb", "php", "vue", "swift", "kt"],
-        "archive" | "zip" | "compressed" => vec!["zip", "rar", "7z", "tar", "gz", "bz2"],
-        "spreadsheet" | "sheet" | "excel" => vec!["xls", "xlsx", "csv", "ods"],
-        "presentation" | "slides" | "ppt" => vec!["ppt", "pptx", "odp"],
-        "data" | "config" => vec!["json", "xml", "yaml", "yml", "toml", "ini", "conf"],
-        _ => vec![],
+impl SearchFilters {
+    /// Whether any filter operator was given
+    pub fn has_filters(&self) -> bool {
+        !self.extensions.is_empty()
+            || !self.types.is_empty()
+            || !self.record_types.is_empty()
+            || !self.paths.is_empty()
+            || self.duplicates_only
+            || self.has_range_filters()
     }
-}
 
-/// Parses a search query to extract filters
+    /// Whether any size or modified time bound is set
+    pub fn has_range_filters(&self) -> bool {
+        self.min_size.is_some()
+            || self.max_size.is_some()
+            || self.modified_after.is_some()
+            || self.modified_before.is_some()
+    }
+
+    /// Checks a result against every filter (record type, folder, extension/type, size, date)
+    pub fn matches_result(&self, result: &SearchResult) -> bool {
+        // Filter by record type (in:files, in:bookmarks, in:history)
+        if !matches_record_type_filter(&result.record_type, self) {
+            return false;
+        }
+
+        // Folder, extension, duplicate and range filters only apply to files; URLs are excluded
+        // (duplicates themselves are narrowed by the caller, which has the content hashes)
+        let has_file_filter = !self.paths.is_empty()
+            || !self.extensions.is_empty()
+            || !self.types.is_empty()
+            || self.duplicates_only
+            || self.has_range_filters();
+        if !has_file_filter {
+            return true;
+        }
+        if result.record_type != "file" && result.record_type != SYMBOL_RECORD_TYPE {
+            return false;
+        }
+
+        matches_path_filter(&result.path, self)
+            && matches_extension_filter(&result.extension, self)
+            && self.min_size.is_none_or(|min| result.size >= min)
+            && self.max_size.is_none_or(|max| result.size <= max)
+            && self.modified_after.is_none_or(|after| result.modified_time >= after)
+            && self.modified_before.is_none_or(|before| result.modified_time <= before)
+    }
+
+    /// Replaces folder scopes that name an indexed folder (like `under:work`) with its full path
+    ///
+    /// Scopes that match no indexed folder are kept as given, so full paths and
+    /// partial directory names still work.
+    pub fn resolve_folder_scopes(&mut self, indexed_folders: &[String]) {
+        let mut resolved = Vec::new();
+        for scope in &self.paths {
+            let matching: Vec<&String> = indexed_folders
+                .iter()
+                .filter(|folder| {
+                    split_path_components(folder)
+                        .last()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(scope))
+                })
+                .collect();
+            if matching.is_empty() {
+                resolved.push(scope.clone());
+            } else {
+                resolved.extend(matching.into_iter().cloned());
+            }
+        }
+        self.paths = resolved;
+    }
+}
+
+/// Computes a fast (xxh3) hash of a file's content
+///
+/// Files up to `HASH_FULL_LIMIT` are hashed in full. Larger files hash their size plus
+/// the first and last `HASH_SAMPLE_SIZE` bytes, so big media files stay cheap to check.
+pub fn hash_file_content(path: &Path) -> std::io::Result<u64> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = fs::File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+
+    if size <= HASH_FULL_LIMIT {
+        let mut content = Vec::with_capacity(size as usize);
+        file.read_to_end(&mut content)?;
+        hasher.update(&content);
+    } else {
+        let mut sample = vec![0u8; HASH_SAMPLE_SIZE as usize];
+        hasher.update(&size.to_le_bytes());
+        file.read_exact(&mut sample)?;
+        hasher.update(&sample);
+        file.seek(SeekFrom::End(-(HASH_SAMPLE_SIZE as i64)))?;
+        file.read_exact(&mut sample)?;
+        hasher.update(&sample);
+    }
+
+    Ok(hasher.digest())
+}
+
+/// Hashes a file's entire content, reusing the last hash while its size and mtime are unchanged
+fn full_content_hash_cached(path: &Path) -> std::io::Result<u64> {
+    let metadata = fs::metadata(path)?;
+    let size = metadata.len();
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or(0);
+    let key = path.to_string_lossy().to_string();
+    let cached = FULL_HASH_CACHE.lock().map_err(|e| std::io::Error::other(e.to_string()))?.get(&key).copied();
+    if let Some((cached_size, cached_modified, hash)) = cached {
+        if cached_size == size && cached_modified == modified {
+            return Ok(hash);
+        }
+    }
+
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    let mut reader = std::io::BufReader::new(fs::File::open(path)?);
+    let mut buffer = vec![0u8; HASH_SAMPLE_SIZE as usize];
+    loop {
+        let read = std::io::Read::read(&mut reader, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    let hash = hasher.digest();
+    let mut cache = FULL_HASH_CACHE.lock().map_err(|e| std::io::Error::other(e.to_string()))?;
+    if cache.len() >= FULL_HASH_CACHE_LIMIT {
+        cache.clear();
+    }
+    cache.insert(key, (size, modified, hash));
+    Ok(hash)
+}
+
+/// Moves a path from one root prefix to another, or None if it isn't under `from`
+///
+/// The prefix must end at a path component boundary and is compared case-insensitively
+/// for Windows-style prefixes. The remainder is re-joined with the separator style of
+/// `to`, so `D:\Data\a\b.txt` rebased to `/mnt/data` becomes `/mnt/data/a/b.txt`.
+pub fn rebase_path(path: &str, from: &str, to: &str) -> Option<String> {
+    let from = from.trim_end_matches(['/', '\\']);
+    if from.is_empty() || path.len() < from.len() || !path.is_char_boundary(from.len()) {
+        return None;
+    }
+    let (prefix, rest) = path.split_at(from.len());
+    let windows_style = from.contains(['\\', ':']);
+    let prefix_matches = if windows_style { prefix.eq_ignore_ascii_case(from) } else { prefix == from };
+    if !prefix_matches || !(rest.is_empty() || rest.starts_with(['/', '\\'])) {
+        return None;
+    }
+
+    let separator = if to.contains('\\') {
+        '\\'
+    } else if to.contains('/') {
+        '/'
+    } else {
+        std::path::MAIN_SEPARATOR
+    };
+    let mut rebased = to.trim_end_matches(['/', '\\']).to_string();
+    for component in rest.split(['/', '\\']).filter(|c| !c.is_empty()) {
+        rebased.push(separator);
+        rebased.push_str(component);
+    }
+    Some(rebased)
+}
+
+/// Language of a document's text, which decides how its content is analyzed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentLanguage {
+    English,
+    Chinese,
+    Other,
+}
+
+/// Guesses the language of a text from a sample of its start
+///
+/// Text with a noticeable share of CJK characters is Chinese. Text written almost
+/// entirely in ASCII letters is English if frequent English words make up a fair
+/// share of it (or if it is too short to tell).
+pub fn detect_language(text: &str) -> ContentLanguage {
+    let sample: String = text.chars().take(LANGUAGE_SAMPLE_CHARS).collect();
+    let (mut cjk, mut ascii, mut other) = (0usize, 0usize, 0usize);
+    for c in sample.chars().filter(|c| c.is_alphabetic()) {
+        if TantivyEngine::contains_chinese(c.encode_utf8(&mut [0; 4])) {
+            cjk += 1;
+        } else if c.is_ascii() {
+            ascii += 1;
+        } else {
+            other += 1;
+        }
+    }
+
+    let letters = cjk + ascii + other;
+    if letters == 0 {
+        return ContentLanguage::Other;
+    }
+    // A CJK character carries about as much as a whole English word
+    if cjk * 5 >= letters {
+        return ContentLanguage::Chinese;
+    }
+    if ascii * 10 < letters * 9 {
+        return ContentLanguage::Other;
+    }
+
+    let words: Vec<String> = sample
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.len() < MIN_LANGUAGE_SAMPLE_WORDS {
+        return ContentLanguage::English;
+    }
+    let markers = words.iter().filter(|w| ENGLISH_MARKER_WORDS.contains(&w.as_str())).count();
+    if markers * 20 >= words.len() {
+        ContentLanguage::English
+    } else {
+        ContentLanguage::Other
+    }
+}
+
+/// The first `max` lines containing any of the (lowercase) terms, with the matched characters
+///
+/// A term only matches at the start of a word, so "in" doesn't light up inside "main";
+/// terms shorter than `MIN_CORRECTABLE_WORD_LEN` must be the whole word, while longer
+/// ones (stems included) may start a longer word. Chinese has no word separators, so
+/// Chinese characters count as boundaries. Long lines are cut to an excerpt around the
+/// first match; positions are char indices into the returned text.
+pub fn find_line_matches(content: &str, terms: &[String], max: usize) -> Vec<LineMatch> {
+    let terms: Vec<Vec<char>> = terms.iter().map(|t| t.chars().collect()).filter(|t: &Vec<char>| !t.is_empty()).collect();
+    let is_word_char = |c: &char| c.is_alphanumeric() && !TantivyEngine::contains_chinese(c.encode_utf8(&mut [0; 4]));
+    let mut matches = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        if matches.len() >= max {
+            break;
+        }
+        // Lowercase char by char so indices line up with the original line
+        let chars: Vec<char> = line.chars().collect();
+        let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+        let mut positions = std::collections::BTreeSet::new();
+        for term in &terms {
+            if term.len() > lower.len() {
+                continue;
+            }
+            let whole_word = term.len() < MIN_CORRECTABLE_WORD_LEN;
+            for (start, window) in lower.windows(term.len()).enumerate() {
+                let end = start + term.len();
+                let starts_word = start == 0 || !is_word_char(&lower[start - 1]) || !is_word_char(&term[0]);
+                let ends_word = lower.get(end).is_none_or(|c| !is_word_char(c)) || !is_word_char(&term[term.len() - 1]);
+                if window == term.as_slice() && starts_word && (!whole_word || ends_word) {
+                    positions.extend(start..end);
+                }
+            }
+        }
+        if let Some(line_match) = line_excerpt(index as u64 + 1, &chars, positions) {
+            matches.push(line_match);
+        }
+    }
+    matches
+}
+
+/// Fills in which lines of each text file contain one of `terms`
+///
+/// Positions aren't stored in the index for this, so the files are re-read; this
+/// only runs on the few results that are returned.
+pub fn add_line_matches(results: &mut [SearchResult], terms: &[String]) {
+    if terms.is_empty() {
+        return;
+    }
+    for result in results.iter_mut() {
+        if result.record_type != "file" || !SUPPORTED_TEXT_EXTS.contains(&result.extension.to_lowercase().as_str()) {
+            continue;
+        }
+        if let Ok(content) = read_text_file(Path::new(&result.path)) {
+            result.line_matches = find_line_matches(&content, terms, MAX_LINE_MATCHES);
+        }
+    }
+}
+
+/// Cuts a line down to an excerpt around its first matched character
+///
+/// Leading indentation is dropped, and a long line keeps some context before the
+/// first match. Returns None if nothing on the line matched.
+fn line_excerpt(line: u64, chars: &[char], positions: std::collections::BTreeSet<usize>) -> Option<LineMatch> {
+    let first = *positions.iter().next()?;
+    let indent = chars.iter().take_while(|c| c.is_whitespace()).count();
+    let start = if first >= indent + MAX_LINE_MATCH_CHARS - LINE_MATCH_CONTEXT_CHARS {
+        first - LINE_MATCH_CONTEXT_CHARS
+    } else {
+        indent
+    };
+    let end = chars.len().min(start + MAX_LINE_MATCH_CHARS);
+    Some(LineMatch {
+        line,
+        text: chars[start..end].iter().collect::<String>().trim_end().to_string(),
+        match_positions: positions.into_iter().filter(|&p| p >= start && p < end).map(|p| p - start).collect(),
+    })
+}
+
+/// Compiles the pattern of a regex (`re:`) search
+///
+/// Patterns are user input, so an invalid one is reported rather than treated as a bug.
+pub fn compile_search_regex(pattern: &str) -> tantivy::Result<regex::Regex> {
+    regex::RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| tantivy::TantivyError::InvalidArgument(format!("Invalid regex: {}", e)))
+}
+
+/// Lines of a text that match a regex, at most `max`
+pub fn regex_line_matches(content: &str, regex: &regex::Regex, max: usize) -> Vec<LineMatch> {
+    let mut matches = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if matches.len() >= max {
+            break;
+        }
+        let mut positions = std::collections::BTreeSet::new();
+        for found in regex.find_iter(line) {
+            // Match ranges are byte offsets; highlights are char positions
+            let start = line[..found.start()].chars().count();
+            positions.extend(start..start + found.as_str().chars().count());
+        }
+        let chars: Vec<char> = line.chars().collect();
+        if let Some(line_match) = line_excerpt(index as u64 + 1, &chars, positions) {
+            matches.push(line_match);
+        }
+    }
+    matches
+}
+
+/// Lowercase words that every match of a regex contains, used to narrow candidates with the index
+///
+/// Only literal text the regex can't skip counts: alternations, optional parts and
+/// classes end a literal run. Runs are split into words the way the content field's
+/// tokenizer splits text, and words too short to be selective are dropped.
+pub fn regex_required_words(pattern: &str) -> Vec<String> {
+    let Ok(hir) = regex_syntax::parse(pattern) else {
+        return Vec::new();
+    };
+    let mut literals = Vec::new();
+    let mut current = String::new();
+    collect_required_literals(&hir, &mut current, &mut literals);
+    literals.push(current);
+
+    let mut words: Vec<String> = Vec::new();
+    for literal in &literals {
+        for word in literal.split(|c: char| !c.is_alphanumeric()) {
+            if word.chars().count() >= MIN_CORRECTABLE_WORD_LEN && !words.iter().any(|w| w == word) {
+                words.push(word.to_string());
+            }
+        }
+    }
+    words
+}
+
+/// Appends the literal text a regex must match to `current`, moving finished runs to `literals`
+fn collect_required_literals(hir: &regex_syntax::hir::Hir, current: &mut String, literals: &mut Vec<String>) {
+    use regex_syntax::hir::HirKind;
+    match hir.kind() {
+        HirKind::Literal(literal) => current.push_str(&String::from_utf8_lossy(&literal.0).to_lowercase()),
+        // `(?i)x` is a class of the case variants of x
+        HirKind::Class(class) => match case_insensitive_char(class) {
+            Some(c) => current.push(c),
+            None => literals.push(std::mem::take(current)),
+        },
+        HirKind::Capture(capture) => collect_required_literals(&capture.sub, current, literals),
+        HirKind::Concat(parts) => {
+            for part in parts {
+                collect_required_literals(part, current, literals);
+            }
+        }
+        HirKind::Repetition(repetition) if repetition.min > 0 => {
+            literals.push(std::mem::take(current));
+            collect_required_literals(&repetition.sub, current, literals);
+            literals.push(std::mem::take(current));
+        }
+        // Anchors and word boundaries match no text, so they don't break a run
+        HirKind::Look(_) | HirKind::Empty => {}
+        _ => literals.push(std::mem::take(current)),
+    }
+}
+
+/// The single character a class stands for when it only differs by case, lowercased
+fn case_insensitive_char(class: &regex_syntax::hir::Class) -> Option<char> {
+    let regex_syntax::hir::Class::Unicode(class) = class else {
+        return None;
+    };
+    let mut chars = class.iter().flat_map(|range| range.start()..=range.end()).take(5);
+    let first = chars.next()?.to_lowercase().next()?;
+    chars.all(|c| c.to_lowercase().eq(std::iter::once(first))).then_some(first)
+}
+
+/// Outcome of scanning files for a regex
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RegexScanSummary {
+    /// Files read before the scan finished or stopped
+    pub files_scanned: usize,
+    /// Stopped because `limit` files matched
+    pub limit_reached: bool,
+    /// Stopped because `REGEX_SEARCH_TIMEOUT` ran out
+    pub timed_out: bool,
+}
+
+/// Reads candidate files in parallel and sends each one with matching lines as soon as it is found
+///
+/// Files are taken in the given order. The scan stops once `limit` files matched, the
+/// timeout ran out or the search was cancelled; files sent so far stay valid results.
+pub fn scan_files_for_regex(
+    candidates: &[SearchResult],
+    regex: &regex::Regex,
+    limit: usize,
+    timeout: Duration,
+    cancelled: &AtomicBool,
+    sender: &Sender<SearchResult>,
+) -> RegexScanSummary {
+    let deadline = Instant::now() + timeout;
+    let next = AtomicUsize::new(0);
+    let scanned = AtomicUsize::new(0);
+    let found = AtomicUsize::new(0);
+    let timed_out = AtomicBool::new(false);
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_REGEX_SCAN_THREADS)
+        .min(candidates.len().max(1));
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                if cancelled.load(Ordering::SeqCst) || found.load(Ordering::SeqCst) >= limit {
+                    break;
+                }
+                if Instant::now() >= deadline {
+                    timed_out.store(true, Ordering::SeqCst);
+                    break;
+                }
+                let Some(candidate) = candidates.get(next.fetch_add(1, Ordering::SeqCst)) else {
+                    break;
+                };
+                scanned.fetch_add(1, Ordering::SeqCst);
+
+                // Files that vanished or aren't UTF-8 simply don't match
+                let Ok(content) = read_text_file(Path::new(&candidate.path)) else {
+                    continue;
+                };
+                let line_matches = regex_line_matches(&content, regex, MAX_REGEX_LINE_MATCHES);
+                if line_matches.is_empty() || found.fetch_add(1, Ordering::SeqCst) >= limit {
+                    continue;
+                }
+                let result = SearchResult {
+                    score: line_matches.len() as f32,
+                    line: line_matches.first().map(|m| m.line),
+                    line_matches,
+                    ..candidate.clone()
+                };
+                if sender.send(result).is_err() {
+                    break; // Nobody is listening anymore
+                }
+            });
+        }
+    });
+
+    let found = found.load(Ordering::SeqCst);
+    RegexScanSummary {
+        files_scanned: scanned.load(Ordering::SeqCst),
+        limit_reached: found >= limit,
+        timed_out: timed_out.load(Ordering::SeqCst) && found < limit,
+    }
+}
+
+/// Reads a text file, skipping files that are too large (>1MB)
+fn read_text_file(path: &Path) -> Result<String, std::io::Error> {
+    if let Ok(metadata) = fs::metadata(path) {
+        if metadata.len() > 1024 * 1024 {
+            return Ok(String::new()); // Skip large files
+        }
+    }
+    fs::read_to_string(path)
+}
+
+/// Registers the tokenizers the schema refers to besides tantivy's built-in ones
+fn register_tokenizers(index: &Index) {
+    index.tokenizers().register(ENGLISH_TOKENIZER, english_analyzer());
+    index.tokenizers().register(CHINESE_WORDS_TOKENIZER, TextAnalyzer::builder(ChineseWordsTokenizer).filter(LowerCaser).build());
+    index.tokenizers().register(
+        CODE_TOKENIZER,
+        TextAnalyzer::builder(CodeTokenizer)
+            .filter(RemoveLongFilter::limit(40))
+            .filter(LowerCaser)
+            .build(),
+    );
+}
+
+/// Whether files with this extension are indexed as source code (the `type:code` category)
+fn is_code_extension(extension: &str) -> bool {
+    get_type_extensions("code").contains(&extension.to_lowercase().as_str())
+}
+
+/// Splits source code into identifiers, emitting each identifier and then its sub-words
+///
+/// `getUserName`, `get_user_name` and `get-user-name` all produce the whole identifier
+/// followed by `get`, `user` and `name`. Sub-words break at `_`, `-` and case changes;
+/// a run of capitals keeps together (`HTTPServer` gives `HTTP` and `Server`).
+#[derive(Clone)]
+struct CodeTokenizer;
+
+/// Tokens computed up front; also used by `ChineseWordsTokenizer`
+struct CodeTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Tokenizer for CodeTokenizer {
+    type TokenStream<'a> = CodeTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CodeTokenStream {
+        let mut tokens = Vec::new();
+        let mut position = 0;
+        for (start, identifier) in code_identifiers(text) {
+            let parts = identifier_parts(identifier);
+            tokens.push(Token {
+                offset_from: start,
+                offset_to: start + identifier.len(),
+                position,
+                text: identifier.to_string(),
+                position_length: parts.len().max(1),
+            });
+            if parts.len() > 1 {
+                for (offset, part) in parts {
+                    tokens.push(Token {
+                        offset_from: start + offset,
+                        offset_to: start + offset + part.len(),
+                        position,
+                        text: part.to_string(),
+                        position_length: 1,
+                    });
+                    position += 1;
+                }
+            } else {
+                position += 1;
+            }
+        }
+        CodeTokenStream { tokens, index: 0 }
+    }
+}
+
+impl TokenStream for CodeTokenStream {
+    fn advance(&mut self) -> bool {
+        self.index += 1;
+        self.index <= self.tokens.len()
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+/// Splits text into the words jieba finds, keeping only those with Chinese characters
+///
+/// Other words are already completed from the default-tokenized fields.
+#[derive(Clone)]
+struct ChineseWordsTokenizer;
+
+impl Tokenizer for ChineseWordsTokenizer {
+    type TokenStream<'a> = CodeTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CodeTokenStream {
+        let mut tokens = Vec::new();
+        let mut offset = 0;
+        for word in JIEBA.cut(text, true) {
+            if TantivyEngine::contains_chinese(word) {
+                tokens.push(Token {
+                    offset_from: offset,
+                    offset_to: offset + word.len(),
+                    position: tokens.len(),
+                    text: word.to_string(),
+                    position_length: 1,
+                });
+            }
+            offset += word.len();
+        }
+        CodeTokenStream { tokens, index: 0 }
+    }
+}
+
+/// Identifiers in a text with their byte offsets: runs of letters, digits and `_`,
+/// joined by single `-` (kebab-case names in CSS, HTML and file paths)
+fn code_identifiers(text: &str) -> Vec<(usize, &str)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut identifiers = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let joins_kebab = c == '-' && start.is_some() && chars.peek().is_some_and(|&(_, next)| is_word(next));
+        if is_word(c) || joins_kebab {
+            start.get_or_insert(i);
+        } else if let Some(s) = start.take() {
+            identifiers.push((s, &text[s..i]));
+        }
+    }
+    if let Some(s) = start {
+        identifiers.push((s, &text[s..]));
+    }
+    identifiers
+}
+
+/// Sub-words of an identifier with their byte offsets within it
+fn identifier_parts(identifier: &str) -> Vec<(usize, &str)> {
+    let chars: Vec<(usize, char)> = identifier.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    for (n, &(i, c)) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if let Some(s) = start.take() {
+                parts.push((s, &identifier[s..i]));
+            }
+            continue;
+        }
+        if let Some(s) = start {
+            if is_case_boundary(&chars, n) {
+                parts.push((s, &identifier[s..i]));
+                start = Some(i);
+            }
+        } else {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        parts.push((s, &identifier[s..]));
+    }
+    parts
+}
+
+/// Whether a new word starts at `chars[n]` because of a case change: `userName` at
+/// the `N`, and `HTTPServer` at the `S` (the last capital of a run before lowercase)
+fn is_case_boundary(chars: &[(usize, char)], n: usize) -> bool {
+    if n == 0 || !chars[n].1.is_uppercase() {
+        return false;
+    }
+    let previous = chars[n - 1].1;
+    previous.is_lowercase()
+        || previous.is_ascii_digit()
+        || (previous.is_uppercase() && chars.get(n + 1).is_some_and(|&(_, next)| next.is_lowercase()))
+}
+
+/// Analysis chain of the `content_en` field
+fn english_analyzer() -> TextAnalyzer {
+    let mut builder = TextAnalyzer::builder(SimpleTokenizer::default())
+        .filter(RemoveLongFilter::limit(40))
+        .filter(LowerCaser)
+        .dynamic();
+    if let Some(stop_words) = StopWordFilter::new(Language::English) {
+        builder = builder.filter_dynamic(stop_words);
+    }
+    builder.filter_dynamic(Stemmer::new(Language::English)).build()
+}
+
+/// Terms a text produces in the `content_en` field
+fn english_terms(text: &str) -> Vec<String> {
+    let mut analyzer = english_analyzer();
+    let mut stream = analyzer.token_stream(text);
+    let mut terms = Vec::new();
+    while stream.advance() {
+        terms.push(stream.token().text.clone());
+    }
+    terms
+}
+
+/// The other words of every synonym group containing `word` (case-insensitive)
+pub fn synonyms_of(groups: &[Vec<String>], word: &str) -> Vec<String> {
+    let mut synonyms = Vec::new();
+    for group in groups {
+        if group.iter().any(|member| member.trim().to_lowercase() == word) {
+            for member in group {
+                let member = member.trim().to_lowercase();
+                if member != word && !member.is_empty() && !synonyms.contains(&member) {
+                    synonyms.push(member);
+                }
+            }
+        }
+    }
+    synonyms
+}
+
+/// Copies of the query words with one word replaced by each of its synonyms
+///
+/// Synonyms are matched per word; a multi-word synonym ("machine learning") replaces
+/// its word with all of its words.
+fn synonym_variants(groups: &[Vec<String>], words: &[&str]) -> Vec<Vec<String>> {
+    let mut variants = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        for synonym in synonyms_of(groups, word) {
+            let mut variant: Vec<String> = words[..i].iter().map(|w| w.to_string()).collect();
+            variant.extend(synonym.split_whitespace().map(str::to_string));
+            variant.extend(words[i + 1..].iter().map(|w| w.to_string()));
+            variants.push(variant);
+        }
+    }
+    variants
+}
+
+/// Largest edit distance accepted as a typo of `word` (longer words tolerate more)
+fn max_typo_distance(word: &str) -> usize {
+    match word.chars().count() {
+        n if n < MIN_CORRECTABLE_WORD_LEN => 0,
+        n if n <= 4 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein automaton of a query word, accepting terms within `max_typo_distance` of it
+///
+/// Lets a term dictionary be searched for close terms without visiting the rest.
+/// Distances are counted in characters.
+struct TypoAutomaton(DFA);
+
+impl TypoAutomaton {
+    /// None for words too short to correct
+    fn for_word(word: &str) -> Option<Self> {
+        let distance = max_typo_distance(word);
+        (distance > 0).then(|| Self(TYPO_AUTOMATON_BUILDERS[distance - 1].build_dfa(word)))
+    }
+
+    /// Edit distance of a term from the word, or None if it is too far
+    fn distance(&self, term: &str) -> Option<usize> {
+        match self.0.eval(term) {
+            Distance::Exact(distance) => Some(distance as usize),
+            Distance::AtLeast(_) => None,
+        }
+    }
+}
+
+impl tantivy_fst::Automaton for TypoAutomaton {
+    type State = u32;
+
+    fn start(&self) -> u32 {
+        self.0.initial_state()
+    }
+
+    fn is_match(&self, state: &u32) -> bool {
+        matches!(self.0.distance(*state), Distance::Exact(_))
+    }
+
+    fn can_match(&self, state: &u32) -> bool {
+        *state != levenshtein_automata::SINK_STATE
+    }
+
+    fn accept(&self, state: &u32, byte: u8) -> u32 {
+        self.0.transition(*state, byte)
+    }
+}
+
+/// Merges results from several indexes into one list
+///
+/// Results are ordered by score, then by the query's sort, and each path is kept once
+/// (the first, so the better-scored hit wins and the personal index wins ties).
+pub fn merge_results(mut results: Vec<SearchResult>, sort: &SortMode, limit: usize) -> Vec<SearchResult> {
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    sort.apply(&mut results);
+    let mut seen_paths = std::collections::HashSet::new();
+    results.retain(|result| seen_paths.insert((result.path.clone(), result.line)));
+    results.truncate(limit);
+    results
+}
+
+/// Directory of a workspace's index
+///
+/// The default workspace keeps the original location, so existing indexes carry over;
+/// other workspaces live under `workspaces/<name>`.
+pub fn workspace_index_path(name: &str) -> PathBuf {
+    let data_dir = dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("worksentry");
+    if name.eq_ignore_ascii_case(DEFAULT_WORKSPACE) {
+        data_dir.join("index")
+    } else {
+        data_dir.join("workspaces").join(name.to_lowercase()).join("index")
+    }
+}
+
+/// Checks whether a path falls under one of the exclusions
+///
+/// An exclusion containing a separator (or a drive prefix) is a folder path and
+/// excludes everything below it; a bare name like `node_modules` excludes any folder
+/// or file of that name, at any depth. Names are compared case-insensitively.
+pub fn is_excluded(path: &Path, exclusions: &[String]) -> bool {
+    exclusions.iter().any(|exclusion| {
+        let exclusion = exclusion.trim().trim_end_matches(['/', '\\']);
+        if exclusion.is_empty() {
+            false
+        } else if exclusion.contains(['/', '\\', ':']) {
+            path.starts_with(exclusion)
+        } else {
+            path.components().any(|c| c.as_os_str().to_string_lossy().eq_ignore_ascii_case(exclusion))
+        }
+    })
+}
+
+/// Lists the indexable files under a folder, in walk order, skipping excluded folders
+///
+/// Doesn't touch the index, so callers can walk large folders without holding the engine.
+pub fn collect_indexable_files(folder: &str, exclusions: &[String]) -> Vec<PathBuf> {
+    let path = Path::new(folder);
+    if !path.exists() || !path.is_dir() {
+        return Vec::new();
+    }
+
+    walkdir::WalkDir::new(folder)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|entry| !is_excluded(entry.path(), exclusions))
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter(|entry| {
+            entry.path().extension().and_then(|e| e.to_str()).is_some_and(|ext| {
+                let ext = ext.to_lowercase();
+                SUPPORTED_TEXT_EXTS.contains(&ext.as_str()) || SUPPORTED_BINARY_EXTS.contains(&ext.as_str())
+            })
+        })
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// Type categories for type: filter
+pub fn get_type_extensions(type_name: &str) -> Vec<&'static str> {
+    match type_name.to_lowercase().as_str() {
+        "doc" | "document" | "documents" => vec!["pdf", "doc", "docx", "txt", "md", "rtf", "odt", "epub", "mobi"],
+        "app" | "application" | "applications" | "exe" => vec!["exe", "lnk", "app", "bat", "cmd", "msi", "dmg"],
+        "image" | "images" | "img" | "pic" => vec!["jpg", "jpeg", "png", "gif", "bmp", "svg", "webp", "ico", "tiff"],
+        "video" | "videos" | "vid" => vec!["mp4", "mkv", "avi", "mov", "wmv", "webm", "flv"],
+        "audio" | "music" | "sound" => vec!["mp3", "wav", "flac", "ogg", "aac", "m4a", "wma"],
+        "code" | "source" | "src" => vec!["rs", "py", "js", "ts", "tsx", "jsx", "java", "c", "cpp", "h", "go", "rb", "php", "vue", "swift", "kt"],
+        "archive" | "zip" | "compressed" => vec!["zip", "rar", "7z", "tar", "gz", "bz2"],
+        "spreadsheet" | "sheet" | "excel" => vec!["xls", "xlsx", "csv", "ods"],
+        "presentation" | "slides" | "ppt" => vec!["ppt", "pptx", "odp"],
+        "data" | "config" => vec!["json", "xml", "yaml", "yml", "toml", "ini", "conf"],
+        _ => vec![],
+    }
+}
+
+/// Canonical names of the `type:` categories, as counted in search facets
+pub const TYPE_CATEGORIES: &[&str] = &[
+    "doc", "app", "image", "video", "audio", "code", "archive", "spreadsheet", "presentation", "data",
+];
+
+/// Counts search hits by record type, extension, `type:` category and parent folder
+///
+/// Hits can be added from several searches (or engines); each path (and symbol line)
+/// is counted once. Each count comes with the query operator that narrows a search to
+/// it, so the UI can offer refinements like "PDF (34)". Folders with whitespace in
+/// their path can't be written as a `path:` operator and get no filter.
+#[derive(Default)]
+pub struct FacetCounter {
+    counted: std::collections::HashSet<(String, Option<u64>)>,
+    record_types: std::collections::HashMap<String, usize>,
+    extensions: std::collections::HashMap<String, usize>,
+    folders: std::collections::HashMap<String, usize>,
+}
+
+impl FacetCounter {
+    pub fn add(&mut self, result: &SearchResult) {
+        if !self.counted.insert((result.path.clone(), result.line)) {
+            return;
+        }
+        *self.record_types.entry(result.record_type.clone()).or_default() += 1;
+        if !result.extension.is_empty() {
+            *self.extensions.entry(result.extension.to_lowercase()).or_default() += 1;
+        }
+        let is_file = result.record_type == "file" || result.record_type == SYMBOL_RECORD_TYPE;
+        if let Some(parent) = Path::new(&result.path).parent().filter(|_| is_file) {
+            *self.folders.entry(parent.to_string_lossy().to_string()).or_default() += 1;
+        }
+    }
+
+    pub fn facets(&self) -> SearchFacets {
+        let types = TYPE_CATEGORIES
+            .iter()
+            .map(|&category| {
+                let category_extensions = get_type_extensions(category);
+                let count = self
+                    .extensions
+                    .iter()
+                    .filter(|(extension, _)| category_extensions.contains(&extension.as_str()))
+                    .map(|(_, count)| count)
+                    .sum();
+                (category.to_string(), count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect();
+
+        SearchFacets {
+            record_types: top_facet_counts(self.record_types.clone(), usize::MAX, |record_type| match record_type {
+                "file" => Some("in:files".to_string()),
+                "Bookmark" => Some("in:bookmarks".to_string()),
+                "History" => Some("in:history".to_string()),
+                SYMBOL_RECORD_TYPE => Some("in:symbols".to_string()),
+                _ => None,
+            }),
+            extensions: top_facet_counts(self.extensions.clone(), MAX_FACET_EXTENSIONS, |extension| Some(format!("ext:{}", extension))),
+            types: top_facet_counts(types, usize::MAX, |category| Some(format!("type:{}", category))),
+            folders: top_facet_counts(self.folders.clone(), MAX_FACET_FOLDERS, |folder| {
+                (!folder.contains(char::is_whitespace)).then(|| format!("path:{}", folder))
+            }),
+        }
+    }
+}
+
+/// Facet counts over a list of search hits (see `FacetCounter`)
+pub fn facet_counts(results: &[SearchResult]) -> SearchFacets {
+    let mut counter = FacetCounter::default();
+    results.iter().for_each(|result| counter.add(result));
+    counter.facets()
+}
+
+/// The `max` largest counts, ties broken by value so the order is stable
+fn top_facet_counts(
+    counts: std::collections::HashMap<String, usize>,
+    max: usize,
+    filter: impl Fn(&str) -> Option<String>,
+) -> Vec<FacetCount> {
+    let mut facets: Vec<FacetCount> = counts
+        .into_iter()
+        .map(|(value, count)| FacetCount { filter: filter(&value), value, count })
+        .collect();
+    facets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    facets.truncate(max);
+    facets
+}
+
+/// Parses a search query to extract filters
 /// 
 /// Supported syntax:
 /// - `ext:pdf` or `ext:pdf,docx` - filter by specific extensions
 /// - `type:doc` - filter by type category (doc, app, image, video, audio, code, archive)
 /// - `in:files` or `in:bookmarks` or `in:history` - filter by record type
+/// - `size:>10mb`, `size:<=500kb`, `size:1kb..5mb` - filter by file size (bare value means at least)
+/// - `modified:today`, `modified:2024-06-01`, `modified:>2024-06-01`, `modified:<7d` - filter by modified time
+/// - `path:D:\Work\Reports` or `under:work` - filter by folder (full path or indexed folder name)
+/// - `dup:` - only files with identical content elsewhere in the index
+/// - `sort:modified`, `sort:size`, `sort:name` - order results, with optional `-asc`/`-desc` suffix
+/// - `sym:parse_query` or `in:symbols` - definitions in source files instead of files
+/// - `re:TODO\(\w+\)` - lines of text files matching a regex (a single word; `\s` for spaces)
+/// - `grep:foo.bar()` - lines of text files containing the text exactly (not a regex)
 /// 
 /// Example: "report ext:pdf type:doc" -> query="report", extensions=["pdf"], types=["doc"]
 pub fn parse_search_query(input: &str) -> SearchFilters {
@@ -969,6 +3453,7 @@ pub fn parse_search_query(input: &str) -> SearchFilters {
                     "file" | "files" => filters.record_types.push("file".to_string()),
                     "bookmark" | "bookmarks" => filters.record_types.push("Bookmark".to_string()),
                     "history" => filters.record_types.push("History".to_string()),
+                    "symbol" | "symbols" => filters.record_types.push(SYMBOL_RECORD_TYPE.to_string()),
                     "web" | "browser" => {
                         filters.record_types.push("Bookmark".to_string());
                         filters.record_types.push("History".to_string());
@@ -976,6 +3461,68 @@ pub fn parse_search_query(input: &str) -> SearchFilters {
                     _ => {}
                 }
             }
+        } else if let Some(size_value) = part.strip_prefix("size:") {
+            // Handle size:>10mb, size:1kb..5mb
+            if let Some((min, max)) = parse_size_filter(size_value) {
+                if let Some(min) = min {
+                    filters.min_size = Some(filters.min_size.map_or(min, |m| m.max(min)));
+                }
+                if let Some(max) = max {
+                    filters.max_size = Some(filters.max_size.map_or(max, |m| m.min(max)));
+                }
+            }
+        } else if let Some(path_value) = part.strip_prefix("path:").or_else(|| part.strip_prefix("under:")) {
+            // Handle path:D:\Work or under:work (paths may contain commas, so no splitting)
+            let path_clean = path_value.trim_end_matches(['/', '\\']);
+            if !path_clean.is_empty() {
+                filters.paths.push(path_clean.to_string());
+            }
+        } else if let Some(dup_value) = part.strip_prefix("dup:") {
+            // Handle dup: or dup:yes (files with identical copies elsewhere)
+            if matches!(dup_value.to_lowercase().as_str(), "" | "yes" | "true" | "only") {
+                filters.duplicates_only = true;
+            }
+        } else if let Some(ws_value) = part.strip_prefix("ws:").or_else(|| part.strip_prefix("workspace:")) {
+            // Handle ws:all or ws:work,home (search other workspaces' indexes)
+            for ws in ws_value.split(',') {
+                let ws_clean = ws.trim().to_lowercase();
+                if !ws_clean.is_empty() {
+                    filters.workspaces.push(ws_clean);
+                }
+            }
+        } else if let Some(symbol_value) = part.strip_prefix("sym:").or_else(|| part.strip_prefix("symbol:")) {
+            // Handle sym:parse_search_query (or a bare sym: before the name)
+            if !filters.record_types.iter().any(|r| r == SYMBOL_RECORD_TYPE) {
+                filters.record_types.push(SYMBOL_RECORD_TYPE.to_string());
+            }
+            if !symbol_value.is_empty() {
+                query_parts.push(symbol_value);
+            }
+        } else if let Some(regex_value) = part.strip_prefix("re:") {
+            // Handle re:TODO\(\w+\) (one word; use \s to match spaces)
+            if !regex_value.is_empty() {
+                filters.regex = Some(regex_value.to_string());
+            }
+        } else if let Some(literal) = part.strip_prefix("grep:") {
+            // Handle grep:foo.bar() as literal text; escaped, its words still narrow the candidates
+            if !literal.is_empty() {
+                filters.regex = Some(regex::escape(literal));
+            }
+        } else if let Some(sort_value) = part.strip_prefix("sort:") {
+            // Handle sort:modified, sort:size-asc, sort:name-desc
+            if let Some(sort) = SortMode::parse(sort_value) {
+                filters.sort = sort;
+            }
+        } else if let Some(modified_value) = part.strip_prefix("modified:") {
+            // Handle modified:today, modified:>2024-06-01, modified:<7d
+            if let Some((after, before)) = parse_modified_filter(modified_value) {
+                if let Some(after) = after {
+                    filters.modified_after = Some(filters.modified_after.map_or(after, |m| m.max(after)));
+                }
+                if let Some(before) = before {
+                    filters.modified_before = Some(filters.modified_before.map_or(before, |m| m.min(before)));
+                }
+            }
         } else {
             // Regular query term
             query_parts.push(part);
@@ -986,6 +3533,151 @@ pub fn parse_search_query(input: &str) -> SearchFilters {
     filters
 }
 
+/// Splits a leading comparison operator (`>`, `>=`, `<`, `<=`, `=`) off a filter value
+fn split_comparator(value: &str) -> (&str, &str) {
+    for op in [">=", "<=", ">", "<", "="] {
+        if let Some(rest) = value.strip_prefix(op) {
+            return (op, rest);
+        }
+    }
+    ("", value)
+}
+
+/// Parses a size such as `500`, `10kb`, `1.5mb` or `2g` into bytes (1024-based units)
+fn parse_size_value(value: &str) -> Option<u64> {
+    let value = value.trim().to_lowercase();
+    let split_at = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split_at);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: u64 = match unit {
+        "" | "b" => 1,
+        "k" | "kb" => 1024,
+        "m" | "mb" => 1024 * 1024,
+        "g" | "gb" => 1024 * 1024 * 1024,
+        "t" | "tb" => 1024 * 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    Some((number * multiplier as f64) as u64)
+}
+
+/// Parses the value of a `size:` operator into inclusive (min, max) byte bounds
+fn parse_size_filter(value: &str) -> Option<(Option<u64>, Option<u64>)> {
+    if let Some((low, high)) = value.split_once("..") {
+        let min = if low.is_empty() { None } else { Some(parse_size_value(low)?) };
+        let max = if high.is_empty() { None } else { Some(parse_size_value(high)?) };
+        if min.is_none() && max.is_none() {
+            return None;
+        }
+        return Some((min, max));
+    }
+
+    let (op, rest) = split_comparator(value);
+    let size = parse_size_value(rest)?;
+    match op {
+        ">" => Some((Some(size.saturating_add(1)), None)),
+        "<" => Some((None, Some(size.saturating_sub(1)))),
+        "<=" => Some((None, Some(size))),
+        "=" => Some((Some(size), Some(size))),
+        _ => Some((Some(size), None)), // ">=" or bare value
+    }
+}
+
+/// A point in time written in a `modified:` operator
+enum TimeValue {
+    /// A calendar day (`2024-06-01`, `today`, `yesterday`) as inclusive [start, end] timestamps
+    Day(i64, i64),
+    /// A relative age (`7d`, `12h`, `2w`, `1y`) as the cutoff timestamp `now - age`
+    Age(i64),
+}
+
+/// Returns the local [start, end] unix timestamps of a calendar day
+fn local_day_bounds(date: chrono::NaiveDate) -> Option<(i64, i64)> {
+    use chrono::TimeZone;
+    let start = chrono::Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()?
+        .timestamp();
+    let next = chrono::Local
+        .from_local_datetime(&date.succ_opt()?.and_hms_opt(0, 0, 0)?)
+        .earliest()?
+        .timestamp();
+    Some((start, next - 1))
+}
+
+/// Parses a single date (`2024-06-01`, `today`, `yesterday`) or age (`7d`, `12h`, `2w`, `1y`)
+fn parse_time_value(value: &str) -> Option<TimeValue> {
+    let value = value.trim().to_lowercase();
+    let today = chrono::Local::now().date_naive();
+
+    let day = match value.as_str() {
+        "today" => Some(today),
+        "yesterday" => today.pred_opt(),
+        _ => chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok(),
+    };
+    if let Some(day) = day {
+        let (start, end) = local_day_bounds(day)?;
+        return Some(TimeValue::Day(start, end));
+    }
+
+    let split_at = value.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = value.split_at(split_at);
+    let number: i64 = number.parse().ok()?;
+    let seconds = match unit {
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        "y" => 365 * 86_400,
+        _ => return None,
+    };
+    Some(TimeValue::Age(chrono::Local::now().timestamp() - number * seconds))
+}
+
+/// Parses the value of a `modified:` operator into inclusive (after, before) timestamp bounds
+///
+/// Dates compare by calendar day (`>2024-06-01` means from June 2nd on), while ages
+/// compare by recency (`<7d` means modified within the last seven days).
+fn parse_modified_filter(value: &str) -> Option<(Option<i64>, Option<i64>)> {
+    if let Some((low, high)) = value.split_once("..") {
+        let after = match low {
+            "" => None,
+            _ => Some(match parse_time_value(low)? {
+                TimeValue::Day(start, _) => start,
+                TimeValue::Age(cutoff) => cutoff,
+            }),
+        };
+        let before = match high {
+            "" => None,
+            _ => Some(match parse_time_value(high)? {
+                TimeValue::Day(_, end) => end,
+                TimeValue::Age(cutoff) => cutoff,
+            }),
+        };
+        if after.is_none() && before.is_none() {
+            return None;
+        }
+        return Some((after, before));
+    }
+
+    let (op, rest) = split_comparator(value);
+    match parse_time_value(rest)? {
+        TimeValue::Day(start, end) => match op {
+            ">" => Some((Some(end + 1), None)),
+            ">=" => Some((Some(start), None)),
+            "<" => Some((None, Some(start - 1))),
+            "<=" => Some((None, Some(end))),
+            _ => Some((Some(start), Some(end))),
+        },
+        TimeValue::Age(cutoff) => match op {
+            // Older than the given age
+            ">" | ">=" => Some((None, Some(cutoff))),
+            // Newer than the given age
+            _ => Some((Some(cutoff), None)),
+        },
+    }
+}
+
 /// Checks if a file extension matches the filters
 pub fn matches_extension_filter(file_ext: &str, filters: &SearchFilters) -> bool {
     let ext_lower = file_ext.to_lowercase();
@@ -1011,10 +3703,96 @@ pub fn matches_extension_filter(file_ext: &str, filters: &SearchFilters) -> bool
     false
 }
 
+/// Finds which characters of a filename the query parts matched, for highlighting
+///
+/// Returns char indices into `file_name`. Each part is matched after the previous
+/// one, as a contiguous substring when possible and as an in-order subsequence
+/// otherwise; parts that don't match are skipped.
+pub fn filename_match_positions(query_parts: &[&str], file_name: &str) -> Vec<usize> {
+    // Lowercase char by char so indices line up with the original filename
+    let name_chars: Vec<char> = file_name
+        .chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect();
+    let mut positions = Vec::new();
+    let mut start = 0usize;
+
+    for part in query_parts {
+        let part_chars: Vec<char> = part.chars().flat_map(|c| c.to_lowercase()).collect();
+        if part_chars.is_empty() || start >= name_chars.len() {
+            continue;
+        }
+
+        let substring_at = name_chars[start..]
+            .windows(part_chars.len())
+            .position(|window| window == part_chars.as_slice());
+        if let Some(offset) = substring_at {
+            let first = start + offset;
+            positions.extend(first..first + part_chars.len());
+            start = first + part_chars.len();
+            continue;
+        }
+
+        let mut subsequence = Vec::new();
+        let mut pos = start;
+        for c in &part_chars {
+            match name_chars[pos..].iter().position(|n| n == c) {
+                Some(offset) => {
+                    subsequence.push(pos + offset);
+                    pos += offset + 1;
+                }
+                None => break,
+            }
+        }
+        if subsequence.len() == part_chars.len() {
+            positions.extend(subsequence);
+            start = pos;
+        }
+    }
+
+    positions
+}
+
+/// Splits a path into its non-empty components, accepting both `/` and `\` separators
+pub fn split_path_components(path: &str) -> Vec<&str> {
+    path.split(['/', '\\']).filter(|c| !c.is_empty()).collect()
+}
+
+/// Checks if a file path lies under one of the folder scopes in the filters
+///
+/// Absolute scopes must be a prefix of the path (component-wise, case-insensitive);
+/// relative scopes like `under:src` match those components anywhere in the path.
+pub fn matches_path_filter(path: &str, filters: &SearchFilters) -> bool {
+    if filters.paths.is_empty() {
+        return true;
+    }
+
+    let path_lower = path.to_lowercase();
+    let path_components = split_path_components(&path_lower);
+
+    filters.paths.iter().any(|scope| {
+        let scope_lower = scope.to_lowercase();
+        let scope_components = split_path_components(&scope_lower);
+        if scope_components.is_empty() {
+            return false;
+        }
+
+        if Path::new(scope).is_absolute() || scope.starts_with(['/', '\\']) || scope.contains(':') {
+            path_components.starts_with(&scope_components)
+        } else {
+            path_components
+                .windows(scope_components.len())
+                .any(|window| window == scope_components.as_slice())
+        }
+    })
+}
+
 /// Checks if a record type matches the filters
+///
+/// Symbols are only included when asked for (`sym:` or `in:symbols`).
 pub fn matches_record_type_filter(record_type: &str, filters: &SearchFilters) -> bool {
     if filters.record_types.is_empty() {
-        return true;
+        return record_type != SYMBOL_RECORD_TYPE;
     }
     filters.record_types.iter().any(|r| r == record_type)
 }
@@ -1105,82 +3883,803 @@ mod tests {
     }
 
     #[test]
-    fn test_is_text_indexable() {
+    fn test_is_text_indexable() {
+        let engine = create_test_engine();
+        // Text files where content is indexed
+        assert!(engine.is_text_indexable("txt"));
+        assert!(engine.is_text_indexable("rs"));
+        assert!(engine.is_text_indexable("py"));
+        
+        // Binary files are NOT text indexable
+        assert!(!engine.is_text_indexable("exe"));
+        assert!(!engine.is_text_indexable("epub"));
+        assert!(!engine.is_text_indexable("pdf"));
+    }
+
+    #[test]
+    fn test_index_folder_creates_index() {
+        let dir = tempdir().unwrap();
+        let test_file = dir.path().join("test.txt");
+        File::create(&test_file).unwrap().write_all(b"hello world test content").unwrap();
+
+        let engine = create_test_engine();
+        let result = engine.index_folder(dir.path().to_str().unwrap());
+        assert!(result.is_ok(), "Indexing should succeed, got: {:?}", result);
+        assert!(engine.get_index_path().exists());
+    }
+
+    #[test]
+    fn test_index_folder_invalid_path() {
+        let engine = create_test_engine();
+        let result = engine.index_folder("/nonexistent/path/12345");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_no_duplicates_on_reindex() {
+        let dir = tempdir().unwrap();
+        let test_file = dir.path().join("test.txt");
+        File::create(&test_file).unwrap().write_all(b"hello world unique content").unwrap();
+
+        let engine = create_test_engine();
+        
+        // Index the folder twice
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+        
+        // Should still have only 1 document
+        let count = engine.get_document_count().unwrap();
+        assert_eq!(count, 1, "Should have exactly 1 document, not duplicates");
+    }
+
+    #[test]
+    fn test_incremental_indexing() {
+        let dir = tempdir().unwrap();
+        let test_file = dir.path().join("test.txt");
+        File::create(&test_file).unwrap().write_all(b"original content").unwrap();
+
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+        
+        // Search for original content
+        let results = engine.search("original", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        
+        // Wait for at least 1 second to ensure mtime changes (stored in seconds)
+        thread::sleep(Duration::from_millis(1100));
+        File::create(&test_file).unwrap().write_all(b"modified content").unwrap();
+        
+        // Re-index (should detect the change)
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+        
+        // Search for modified content
+        let results = engine.search("modified", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        
+        // Original content should no longer be found
+        let results = engine.search("original", 10).unwrap();
+        assert_eq!(results.len(), 0);
+    }
+
+    #[test]
+    fn test_collect_and_index_files_in_batches() {
+        let dir = tempdir().unwrap();
+        let nested = dir.path().join("nested");
+        fs::create_dir(&nested).unwrap();
+        File::create(dir.path().join("a.txt")).unwrap().write_all(b"alpha").unwrap();
+        File::create(nested.join("b.md")).unwrap().write_all(b"beta").unwrap();
+        File::create(dir.path().join("skip.unknownext")).unwrap();
+
+        let files = collect_indexable_files(dir.path().to_str().unwrap(), &[]);
+        assert_eq!(files.len(), 2);
+        assert!(collect_indexable_files("/nonexistent/path/12345", &[]).is_empty());
+
+        let engine = create_test_engine();
+        assert_eq!(engine.index_files(&files[..1]).unwrap().added, 1);
+        assert_eq!(engine.get_document_count().unwrap(), 0, "Batches share a writer until it is committed");
+        engine.commit_job_writes().unwrap();
+        assert_eq!(engine.get_document_count().unwrap(), 1);
+
+        let changes = engine.index_files(&files).unwrap();
+        assert_eq!((changes.added, changes.updated), (1, 0), "Unchanged files are skipped");
+        // Any other write commits the job's writes before opening its own writer
+        engine.delete_file("/nonexistent/file.txt").unwrap();
+        assert_eq!(engine.get_document_count().unwrap(), 2);
+        engine.commit_job_writes().unwrap();
+        assert_eq!(engine.get_document_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_exclusions_and_workspace_paths() {
+        let dir = tempdir().unwrap();
+        let modules = dir.path().join("app").join("node_modules");
+        let build = dir.path().join("build");
+        fs::create_dir_all(&modules).unwrap();
+        fs::create_dir_all(&build).unwrap();
+        fs::write(dir.path().join("app").join("main.js"), "app").unwrap();
+        fs::write(modules.join("lib.js"), "dependency").unwrap();
+        fs::write(build.join("out.js"), "generated").unwrap();
+        let folder = dir.path().to_str().unwrap();
+
+        let exclusions = vec!["NODE_MODULES".to_string(), build.to_string_lossy().to_string()];
+        assert!(is_excluded(&modules.join("lib.js"), &exclusions), "Names match any folder, case-insensitively");
+        assert!(is_excluded(&build.join("out.js"), &exclusions), "Paths exclude everything below them");
+        assert!(!is_excluded(&dir.path().join("app").join("main.js"), &exclusions));
+        assert_eq!(collect_indexable_files(folder, &exclusions).len(), 1);
+
+        // Newly excluded files are purged and no longer re-added
+        let mut engine = create_test_engine();
+        engine.index_folder(folder).unwrap();
+        assert_eq!(engine.get_document_count().unwrap(), 3);
+        engine.set_exclusions(exclusions);
+        assert_eq!(engine.purge_excluded_files().unwrap(), 2);
+        assert!(!engine.index_file(&modules.join("lib.js").to_string_lossy()).unwrap());
+        assert_eq!(engine.get_document_count().unwrap(), 1);
+
+        assert_eq!(workspace_index_path(DEFAULT_WORKSPACE), TantivyEngine::new().unwrap().index_path);
+        assert!(workspace_index_path("Work").ends_with(Path::new("workspaces").join("work").join("index")));
+        assert_eq!(parse_search_query("report ws:Work,home").workspaces, vec!["work", "home"]);
+        assert_eq!(parse_search_query("report ws:Work").query, "report");
+    }
+
+    #[test]
+    fn test_shared_index_read_only_and_merge() {
+        let docs = tempdir().unwrap();
+        fs::write(docs.path().join("design_notes.md"), "shared design").unwrap();
+        let index_dir = tempdir().unwrap();
+        let shared_path = index_dir.path().join("shared");
+        TantivyEngine::new_with_path(shared_path.clone()).unwrap()
+            .index_folder(docs.path().to_str().unwrap()).unwrap();
+
+        assert!(TantivyEngine::open_read_only(index_dir.path().join("missing")).is_err());
+        assert!(!index_dir.path().join("missing").exists(), "Read-only engines never create indexes");
+        let shared = TantivyEngine::open_read_only(shared_path).unwrap();
+        let shared_hits = shared.search_launcher("design", 10).unwrap();
+        assert_eq!(shared_hits.len(), 1);
+
+        // The same path from two indexes is listed once, keeping the better hit
+        let mut personal_hit = shared_hits[0].clone();
+        personal_hit.score += 1.0;
+        let mut other = shared_hits[0].clone();
+        other.path = "/elsewhere/design.md".to_string();
+        other.score = 0.1;
+        let merged = merge_results(vec![other, shared_hits[0].clone(), personal_hit.clone()], &SortMode::default(), 10);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].score, personal_hit.score);
+        assert_eq!(merge_results(merged, &SortMode::default(), 1).len(), 1);
+    }
+
+    #[test]
+    fn test_related_documents() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("cache_design.md"), "cache eviction policy design with lru eviction and cache warming").unwrap();
+        fs::write(dir.path().join("cache_rollout.txt"), "rollout plan for the lru cache eviction change").unwrap();
+        fs::write(dir.path().join("holiday.txt"), "beach trip packing list").unwrap();
+        let design = dir.path().join("cache_design.md").to_string_lossy().to_string();
+
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+        engine.index_browser_data(vec![crate::services::browser_extractor::BrowserData {
+            url: "https://example.com/lru".to_string(),
+            title: "LRU cache eviction explained".to_string(),
+            source: "Chrome".to_string(),
+            data_type: "Bookmark".to_string(),
+        }]).unwrap();
+
+        let related = engine.related(&design, 10).unwrap();
+        let names: Vec<&str> = related.iter().map(|r| r.file_name.as_str()).collect();
+        assert_eq!(names.first(), Some(&"cache_rollout.txt"), "Most similar file ranks first: {:?}", names);
+        assert!(names.contains(&"LRU cache eviction explained"), "Bookmark titles are related too: {:?}", names);
+        assert!(!names.contains(&"cache_design.md"), "The source document is skipped");
+        assert!(!names.contains(&"holiday.txt"));
+        assert!(engine.related("/not/indexed.txt", 10).is_err());
+    }
+
+    #[test]
+    fn test_spelling_suggestions() {
+        let distance = |word: &str, term: &str| TypoAutomaton::for_word(word).and_then(|automaton| automaton.distance(term));
+        assert_eq!(distance("progrmaing", "programming"), Some(2));
+        assert_eq!(distance("kitten", "sitting"), None);
+        assert_eq!(distance("氛圍", "氛围"), None, "Too short to correct");
+        assert_eq!(distance("簡體字", "简体字"), None, "Two typos are too many for a short word");
+        assert_eq!(distance("繁體字", "繁体字"), Some(1));
+
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("notes.txt"), "functional programming and programming languages").unwrap();
+        fs::write(dir.path().join("diagram.txt"), "a histogram of program runs").unwrap();
+        fs::write(dir.path().join("budget_report.md"), "quarterly numbers").unwrap();
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let suggestions = engine.suggest_spelling("progrmaing", 3).unwrap();
+        assert_eq!(suggestions.first().map(String::as_str), Some("programming"));
+        // Known words and filter operators are kept, only the typo is corrected
+        assert_eq!(engine.suggest_spelling("functional progrmaing ext:txt", 1).unwrap(), vec!["functional programming ext:txt"]);
+        assert_eq!(engine.suggest_spelling("budgte", 1).unwrap(), vec!["budget"], "Filenames are a source too");
+        assert!(engine.suggest_spelling("programming", 3).unwrap().is_empty());
+        assert!(engine.suggest_spelling("ab ext:pdf", 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_complete_prefix() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("report_2024.txt"), "quarterly revenue review").unwrap();
+        fs::write(dir.path().join("notes.txt"), "revenue forecast and repository links").unwrap();
+        fs::write(dir.path().join("会议.txt"), "我们讨论了项目报告").unwrap();
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let completions = engine.complete_prefix("Re", 5).unwrap();
+        assert_eq!(completions.filename_terms, vec!["report"]);
+        assert_eq!(completions.content_terms.first().map(String::as_str), Some("revenue"), "Most frequent term first");
+        assert!(completions.content_terms.contains(&"repository".to_string()));
+        assert_eq!(engine.complete_prefix("r", 5).unwrap(), TermCompletions::default(), "Single letters are too short");
+        assert_eq!(engine.complete_prefix("revenue", 5).unwrap().content_terms, Vec::<String>::new());
+
+        // Chinese runs are segmented with jieba, so prefixes complete to whole words
+        let completions = engine.complete_prefix("项", 5).unwrap();
+        assert!(completions.content_terms.contains(&"项目".to_string()), "{:?}", completions);
+        assert_eq!(engine.complete_prefix("会", 5).unwrap().filename_terms, vec!["会议"]);
+    }
+
+    #[test]
+    fn test_line_matches() {
+        let terms = vec!["report".to_string()];
+        let content = "Title\n    Quarterly Report due\nnothing here\nreport, report";
+        let matches = find_line_matches(content, &terms, 3);
+        assert_eq!(matches, vec![
+            LineMatch { line: 2, text: "Quarterly Report due".to_string(), match_positions: (10..16).collect() },
+            LineMatch { line: 4, text: "report, report".to_string(), match_positions: (0..6).chain(8..14).collect() },
+        ]);
+        assert_eq!(find_line_matches(content, &terms, 1).len(), 1);
+
+        let long_line = format!("{} report", "x".repeat(500));
+        let matches = find_line_matches(&long_line, &terms, 3);
+        assert_eq!(matches[0].text.chars().count(), LINE_MATCH_CONTEXT_CHARS + 6, "Long lines are cut around the match");
+        assert_eq!(matches[0].match_positions[0], LINE_MATCH_CONTEXT_CHARS);
+
+        // Terms match at word starts only, and short ones only as whole words
+        let terms = vec!["in".to_string(), "a".to_string(), "port".to_string(), "报告".to_string()];
+        assert!(find_line_matches("main data export", &terms, 3).is_empty());
+        let matches = find_line_matches("in a portal 年度报告", &terms, 3);
+        assert_eq!(matches[0].match_positions, vec![0, 1, 3, 5, 6, 7, 8, 14, 15]);
+
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("notes.txt"), "intro\nThe files were indexed overnight\n").unwrap();
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+        let results = engine.search_content(&parse_search_query("indexing"), 10).unwrap();
+        assert_eq!(results[0].line_matches.len(), 1);
+        assert_eq!(results[0].line_matches[0].line, 2, "Stemmed matches are located too");
+        assert_eq!(results[0].line_matches[0].match_positions, (15..20).collect::<Vec<_>>());
+
+        let filters = parse_search_query("indexing");
+        let uncancelled = engine.search_content_cancellable(&filters, 10, &AtomicBool::new(false)).unwrap().unwrap();
+        assert!(uncancelled[0].line_matches.is_empty(), "Matching lines are left to the caller");
+        assert!(engine.search_content_cancellable(&filters, 10, &AtomicBool::new(true)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_facet_counts() {
+        let dir = tempdir().unwrap();
+        let sub = dir.path().join("drafts");
+        fs::create_dir(&sub).unwrap();
+        fs::write(dir.path().join("report.pdf"), "pdf").unwrap();
+        fs::write(sub.join("report-v1.pdf"), "pdf").unwrap();
+        fs::write(sub.join("report-v2.pdf"), "pdf").unwrap();
+        fs::write(sub.join("report.md"), "notes").unwrap();
+        fs::write(sub.join("report.json"), "{}").unwrap();
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let results = engine.search_launcher("report", 20).unwrap();
+        let facets = facet_counts(&results);
+        assert_eq!(facets.record_types, vec![
+            FacetCount { value: "file".to_string(), count: 5, filter: Some("in:files".to_string()) },
+        ]);
+        let extensions: Vec<(&str, usize)> = facets.extensions.iter().map(|f| (f.value.as_str(), f.count)).collect();
+        assert_eq!(extensions, vec![("pdf", 3), ("json", 1), ("md", 1)]);
+        let types: Vec<(&str, usize)> = facets.types.iter().map(|f| (f.value.as_str(), f.count)).collect();
+        assert_eq!(types, vec![("doc", 4), ("data", 1)]);
+        assert_eq!(facets.types[0].filter.as_deref(), Some("type:doc"));
+        assert_eq!(facets.folders[0].value, sub.to_string_lossy());
+        assert_eq!(facets.folders[0].count, 4);
+        assert_eq!(facets.folders.len(), 2);
+
+        assert_eq!(facet_counts(&[]), SearchFacets::default());
+
+        // A search counts all its hits, not just those it returns, and each only once
+        let mut counter = FacetCounter::default();
+        let results = engine.search_launcher_cancellable(parse_search_query("report"), 1, &AtomicBool::new(false), Some(&mut counter)).unwrap();
+        assert_eq!(results.unwrap().len(), 1);
+        engine.search_launcher_cancellable(parse_search_query("report ext:pdf"), 1, &AtomicBool::new(false), Some(&mut counter)).unwrap();
+        assert_eq!(counter.facets(), facets);
+    }
+
+    #[test]
+    fn test_regex_search() {
+        let filters = parse_search_query(r"re:TODO\(\w+\) ext:md");
+        assert_eq!(filters.regex.as_deref(), Some(r"TODO\(\w+\)"));
+        assert_eq!(filters.extensions, vec!["md"]);
+        assert!(filters.query.is_empty());
+
+        // grep: takes its value literally
+        let literal = parse_search_query("grep:config.load(path)").regex.unwrap();
+        assert_eq!(literal, r"config\.load\(path\)");
+        assert_eq!(regex_required_words(&literal), vec!["config", "load", "path"]);
+        let regex = compile_search_regex(&literal).unwrap();
+        assert!(regex.is_match("let c = config.load(path);"));
+        assert!(!regex.is_match("configXload(path)"));
+        let (sender, _receiver) = std::sync::mpsc::channel();
+        let other_workspace = parse_search_query("re:TODO ws:work");
+        assert!(grep_files(&other_workspace, 10, &AtomicBool::new(false), &sender).is_err());
+
+        assert_eq!(regex_required_words(r"TODO\(\w+\)"), vec!["todo"]);
+        assert_eq!(regex_required_words(r"(?i)error E\d{4}: timeout"), vec!["error", "timeout"]);
+        assert_eq!(regex_required_words(r"\bconfig(uration)?_loader\b"), vec!["config", "loader"]);
+        assert!(regex_required_words("foo|bar").is_empty(), "Either branch may match");
+        assert!(compile_search_regex("TODO(").is_err());
+
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("tasks.md"), "# Tasks\nTODO(anna): index pdfs\nTODO: no owner\n").unwrap();
+        fs::write(dir.path().join("done.md"), "Nothing left to do\n").unwrap();
+        fs::write(dir.path().join("more.txt"), "todo(bob) lowercase\nTODO(carl) later\n").unwrap();
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let pattern = r"TODO\(\w+\)";
+        let candidates = engine.regex_candidates(&parse_search_query(""), pattern).unwrap();
+        let mut names: Vec<&str> = candidates.iter().map(|c| c.file_name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["more.txt", "tasks.md"], "The index rules out files without the word");
+
+        let regex = compile_search_regex(pattern).unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let summary = scan_files_for_regex(&candidates, &regex, 10, Duration::from_secs(10), &AtomicBool::new(false), &sender);
+        drop(sender);
+        let mut results: Vec<SearchResult> = receiver.into_iter().collect();
+        results.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        assert_eq!(summary.files_scanned, 2);
+        assert!(!summary.limit_reached && !summary.timed_out);
+        assert_eq!(results[0].line_matches, vec![
+            LineMatch { line: 2, text: "TODO(carl) later".to_string(), match_positions: (0..10).collect() },
+        ], "Regex matching is case-sensitive");
+        assert_eq!(results[1].line, Some(2));
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let summary = scan_files_for_regex(&candidates, &regex, 1, Duration::from_secs(10), &AtomicBool::new(false), &sender);
+        drop(sender);
+        assert_eq!(receiver.into_iter().count(), 1);
+        assert!(summary.limit_reached);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let summary = scan_files_for_regex(&candidates, &regex, 10, Duration::ZERO, &AtomicBool::new(false), &sender);
+        drop(sender);
+        assert_eq!(receiver.into_iter().count(), 0);
+        assert!(summary.timed_out);
+    }
+
+    #[test]
+    fn test_symbol_search() {
+        let rust = "pub struct SearchFilters {\n}\n\npub(crate) async fn parse_search_query(input: &str) {}\nconst MAX_HITS: usize = 3;\nmacro_rules! log_hit {}\n";
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("engine.rs");
+        fs::write(&file, rust).unwrap();
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(engine.get_document_count().unwrap(), 1, "Symbols aren't counted as documents");
+        assert_eq!(engine.get_index_stats(&[]).unwrap().document_count, 1);
+
+        let results = engine.search_launcher("sym:parse_search_query", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].record_type, SYMBOL_RECORD_TYPE);
+        assert_eq!(results[0].file_name, "parse_search_query");
+        assert_eq!(results[0].path, file.to_string_lossy());
+        assert_eq!(results[0].line, Some(4));
+        assert_eq!(results[0].symbol_kind.as_deref(), Some("function"));
+
+        let results = engine.search_launcher("in:symbols ext:rs", 10).unwrap();
+        assert_eq!(results.len(), 4, "Every symbol of the file is listed, not deduplicated by path");
+        let results = engine.search_launcher("search", 10).unwrap();
+        assert!(results.is_empty(), "Symbols stay out of normal searches");
+        assert!(engine.search_content(&parse_search_query("parse_search_query"), 10).unwrap().iter().all(|r| r.record_type == "file"));
+
+        // Re-indexing replaces the symbols, deleting the file removes them
+        fs::write(&file, "fn renamed() {}").unwrap();
+        engine.index_file(&file.to_string_lossy()).unwrap();
+        let names: Vec<String> = engine.search_launcher("sym:", 10).unwrap().into_iter().map(|r| r.file_name).collect();
+        assert_eq!(names, vec!["renamed"]);
+        engine.delete_file(&file.to_string_lossy()).unwrap();
+        assert_eq!(engine.get_document_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_code_tokenizer_splits_identifiers() {
+        let terms = |text: &str| {
+            let mut analyzer = TextAnalyzer::builder(CodeTokenizer).filter(LowerCaser).build();
+            let mut stream = analyzer.token_stream(text);
+            let mut terms = Vec::new();
+            while stream.advance() {
+                terms.push(stream.token().text.clone());
+            }
+            terms
+        };
+        assert_eq!(terms("let n = getUserName();"), vec!["let", "n", "getusername", "get", "user", "name"]);
+        assert_eq!(terms("MAX_RETRY x-large -1"), vec!["max_retry", "max", "retry", "x-large", "x", "large", "1"]);
+        assert_eq!(terms("HTTPServer utf8Decode"), vec!["httpserver", "http", "server", "utf8decode", "utf8", "decode"]);
+
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("user.ts"), "export function getUserName() {}").unwrap();
+        fs::write(dir.path().join("notes.txt"), "getUserName is documented elsewhere").unwrap();
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let results = engine.search_content(&parse_search_query("user name"), 10).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.file_name.as_str()).collect();
+        assert_eq!(names, vec!["user.ts"], "Only code files are split into sub-words");
+        let results = engine.search_content(&parse_search_query("getusername"), 10).unwrap();
+        assert_eq!(results.len(), 2, "The whole identifier is still indexed");
+
+        // Case changes count as word boundaries in the launcher
+        let camel = TantivyEngine::calculate_launcher_score(&["name"], "getUserName.ts").unwrap();
+        let flat = TantivyEngine::calculate_launcher_score(&["name"], "getusername.ts").unwrap();
+        assert!(camel > flat);
+    }
+
+    #[test]
+    fn test_english_content_is_stemmed() {
+        assert_eq!(detect_language("Indexing notes"), ContentLanguage::English);
+        assert_eq!(detect_language("这是一份关于索引的季度报告 with some English"), ContentLanguage::Chinese);
+        assert_eq!(detect_language("Größenänderung später prüfen"), ContentLanguage::Other);
+        let german = "Die Dateien werden nachts neu indiziert und danach sofort wieder durchsucht, damit alle Ergebnisse aktuell bleiben und nichts verloren geht";
+        assert_eq!(detect_language(german), ContentLanguage::Other, "Mostly ASCII but no English words");
+        assert_eq!(english_terms("The indexing of files"), vec!["index", "file"]);
+
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("notes.txt"), "All of the files were indexed overnight").unwrap();
+        fs::write(dir.path().join("zh.txt"), "季度 报告").unwrap();
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let results = engine.search_content(&parse_search_query("indexing"), 10).unwrap();
+        assert_eq!(results.first().map(|r| r.file_name.as_str()), Some("notes.txt"), "Stemming matches other word forms");
+        let results = engine.search_content(&parse_search_query("报告"), 10).unwrap();
+        assert_eq!(results.first().map(|r| r.file_name.as_str()), Some("zh.txt"), "Chinese content is still found");
+    }
+
+    #[test]
+    fn test_synonyms_in_launcher_and_content_search() {
+        let groups = vec![
+            vec!["k8s".to_string(), "Kubernetes".to_string()],
+            vec!["报告".to_string(), "report".to_string()],
+        ];
+        assert_eq!(synonyms_of(&groups, "kubernetes"), vec!["k8s"]);
+        assert_eq!(synonym_variants(&groups, &["k8s", "setup"]), vec![vec!["kubernetes", "setup"]]);
+
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("k8s_setup.md"), "cluster notes").unwrap();
+        fs::write(dir.path().join("kubernetes_guide.md"), "deploying to kubernetes clusters").unwrap();
+        fs::write(dir.path().join("annual_report.txt"), "numbers").unwrap();
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let mut filters = parse_search_query("k8s");
+        assert_eq!(engine.search_launcher_with_filters(filters.clone(), 10).unwrap().len(), 1, "No expansion without synonyms");
+        filters.synonyms = groups.clone();
+        let results = engine.search_launcher_with_filters(filters.clone(), 10).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.file_name.as_str()).collect();
+        assert_eq!(names, vec!["k8s_setup.md", "kubernetes_guide.md"], "Direct matches rank above synonym matches");
+        assert_eq!(results[1].match_positions, (0..10).collect::<Vec<_>>(), "The synonym is highlighted");
+
+        let mut filters = parse_search_query("报告");
+        filters.synonyms = groups.clone();
+        let results = engine.search_launcher_with_filters(filters, 10).unwrap();
+        assert_eq!(results.first().map(|r| r.file_name.as_str()), Some("annual_report.txt"));
+
+        let mut filters = parse_search_query("k8s");
+        filters.synonyms = groups;
+        let results = engine.search_content(&filters, 10).unwrap();
+        assert!(results.iter().any(|r| r.file_name == "kubernetes_guide.md"), "BM25 search expands synonyms too");
+    }
+
+    #[test]
+    fn test_change_detection_uses_size_hash_and_older_mtime() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("notes.txt");
+        fs::write(&file_path, "first draft").unwrap();
+        let original_mtime = fs::metadata(&file_path).unwrap().modified().unwrap();
+        let folder = dir.path().to_str().unwrap();
+
         let engine = create_test_engine();
-        // Text files where content is indexed
-        assert!(engine.is_text_indexable("txt"));
-        assert!(engine.is_text_indexable("rs"));
-        assert!(engine.is_text_indexable("py"));
-        
-        // Binary files are NOT text indexable
-        assert!(!engine.is_text_indexable("exe"));
-        assert!(!engine.is_text_indexable("epub"));
-        assert!(!engine.is_text_indexable("pdf"));
+        assert_eq!(engine.index_files(std::slice::from_ref(&file_path)).unwrap().added, 1);
+        engine.commit_job_writes().unwrap();
+        assert_eq!(engine.index_files(std::slice::from_ref(&file_path)).unwrap(), IndexChanges::default());
+
+        // Same size and mtime, different content: only the hash catches it
+        fs::write(&file_path, "final draft").unwrap();
+        File::options().write(true).open(&file_path).unwrap().set_modified(original_mtime).unwrap();
+        assert_eq!(engine.index_files(std::slice::from_ref(&file_path)).unwrap().updated, 1);
+        engine.commit_job_writes().unwrap();
+        assert_eq!(engine.search("final", 10).unwrap().len(), 1);
+
+        // Restored from a backup with an older timestamp
+        fs::write(&file_path, "first draft").unwrap();
+        let older = original_mtime - Duration::from_secs(3600);
+        File::options().write(true).open(&file_path).unwrap().set_modified(older).unwrap();
+        engine.index_folder(folder).unwrap();
+        assert_eq!(engine.search("first", 10).unwrap().len(), 1);
+        assert!(engine.search("final", 10).unwrap().is_empty());
+
+        // Modified well before it was read: the unchanged mtime is trusted without hashing
+        fs::write(&file_path, "final draft").unwrap();
+        File::options().write(true).open(&file_path).unwrap().set_modified(older).unwrap();
+        assert_eq!(engine.index_files(std::slice::from_ref(&file_path)).unwrap(), IndexChanges::default());
     }
 
     #[test]
-    fn test_index_folder_creates_index() {
+    fn test_hash_file_content() {
         let dir = tempdir().unwrap();
-        let test_file = dir.path().join("test.txt");
-        File::create(&test_file).unwrap().write_all(b"hello world test content").unwrap();
-
-        let engine = create_test_engine();
-        let result = engine.index_folder(dir.path().to_str().unwrap());
-        assert!(result.is_ok(), "Indexing should succeed, got: {:?}", result);
-        assert!(engine.get_index_path().exists());
+        let a = dir.path().join("a.bin");
+        let b = dir.path().join("b.bin");
+        fs::write(&a, b"same bytes").unwrap();
+        fs::write(&b, b"same bytes").unwrap();
+        assert_eq!(hash_file_content(&a).unwrap(), hash_file_content(&b).unwrap());
+
+        // Large files are sampled at both ends
+        let mut large = vec![7u8; (HASH_FULL_LIMIT + 1) as usize];
+        fs::write(&a, &large).unwrap();
+        let large_hash = hash_file_content(&a).unwrap();
+        *large.last_mut().unwrap() = 8;
+        fs::write(&a, &large).unwrap();
+        assert_ne!(hash_file_content(&a).unwrap(), large_hash);
+        assert!(hash_file_content(&dir.path().join("missing.bin")).is_err());
     }
 
     #[test]
-    fn test_index_folder_invalid_path() {
+    fn test_find_duplicates_and_dup_filter() {
+        let dir = tempdir().unwrap();
+        let copies = dir.path().join("copies");
+        fs::create_dir(&copies).unwrap();
+        fs::write(dir.path().join("report.txt"), "quarterly numbers").unwrap();
+        fs::write(copies.join("report (1).txt"), "quarterly numbers").unwrap();
+        fs::write(copies.join("report_final.md"), "quarterly numbers").unwrap();
+        fs::write(dir.path().join("report_draft.txt"), "different numbers").unwrap();
+        fs::write(dir.path().join("empty1.txt"), "").unwrap();
+        fs::write(dir.path().join("empty2.txt"), "").unwrap();
+
         let engine = create_test_engine();
-        let result = engine.index_folder("/nonexistent/path/12345");
-        assert!(result.is_ok());
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let groups = engine.find_duplicates().unwrap();
+        assert_eq!(groups.len(), 1, "Empty files are not duplicates");
+        assert_eq!(groups[0].size, "quarterly numbers".len() as u64);
+        assert_eq!(groups[0].paths.len(), 3);
+        assert_eq!(groups[0].hash.len(), 16);
+
+        let filters = parse_search_query("report dup:");
+        assert!(filters.duplicates_only);
+        assert_eq!(filters.query, "report");
+        assert!(!parse_search_query("report dup:maybe").duplicates_only);
+
+        let results = engine.search_launcher("report dup:", 10).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| r.file_name != "report_draft.txt"));
+        let results = engine.search_content(&parse_search_query("numbers dup:"), 10).unwrap();
+        assert_eq!(results.len(), 3);
+
+        // A path indexed twice is still a single file
+        let lone = dir.path().join("lone.txt");
+        fs::write(&lone, "only copy").unwrap();
+        engine.index_file(lone.to_str().unwrap()).unwrap();
+        let index = engine.get_index().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let term = Term::from_field_text(engine.path_field, lone.to_str().unwrap());
+        let hits = searcher.search(&TermQuery::new(term, IndexRecordOption::Basic), &TopDocs::with_limit(1)).unwrap();
+        let doc: TantivyDocument = searcher.doc(hits[0].1).unwrap();
+        let mut writer: IndexWriter<TantivyDocument> = index.writer(50_000_000).unwrap();
+        writer.add_document(doc).unwrap();
+        writer.commit().unwrap();
+        assert_eq!(engine.find_duplicates().unwrap().len(), 1);
+        assert!(engine.search_launcher("lone dup:", 10).unwrap().is_empty());
+        assert!(engine.search_content(&parse_search_query("only dup:"), 10).unwrap().is_empty());
     }
 
     #[test]
-    fn test_no_duplicates_on_reindex() {
+    fn test_large_duplicates_are_verified_in_full() {
         let dir = tempdir().unwrap();
-        let test_file = dir.path().join("test.txt");
-        File::create(&test_file).unwrap().write_all(b"hello world unique content").unwrap();
+        let mut content = vec![1u8; (HASH_FULL_LIMIT * 2) as usize];
+        fs::write(dir.path().join("video_a.mp4"), &content).unwrap();
+        fs::write(dir.path().join("video_b.mp4"), &content).unwrap();
+        // Same size and both ends, different middle: the sampled hashes collide
+        content[HASH_FULL_LIMIT as usize] = 2;
+        fs::write(dir.path().join("video_c.mp4"), &content).unwrap();
 
         let engine = create_test_engine();
-        
-        // Index the folder twice
         engine.index_folder(dir.path().to_str().unwrap()).unwrap();
-        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
-        
-        // Should still have only 1 document
-        let count = engine.get_document_count().unwrap();
-        assert_eq!(count, 1, "Should have exactly 1 document, not duplicates");
+
+        let groups = engine.find_duplicates().unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].paths.len(), 2);
+        assert!(groups[0].paths.iter().all(|p| !p.ends_with("video_c.mp4")));
+        let results = engine.search_launcher("video dup:", 10).unwrap();
+        assert_eq!(results.len(), 2);
     }
 
     #[test]
-    fn test_incremental_indexing() {
+    fn test_merge_segments_and_stats() {
         let dir = tempdir().unwrap();
-        let test_file = dir.path().join("test.txt");
-        File::create(&test_file).unwrap().write_all(b"original content").unwrap();
+        for i in 0..3 {
+            fs::write(dir.path().join(format!("file{}.txt", i)), format!("content {}", i)).unwrap();
+        }
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+        // Each single-file commit adds a segment, and re-indexing deletes the old document
+        engine.index_file(dir.path().join("file0.txt").to_str().unwrap()).unwrap();
+        engine.index_file(dir.path().join("file1.txt").to_str().unwrap()).unwrap();
+
+        let stats = engine.get_index_stats(&[]).unwrap();
+        assert_eq!(stats.segment_count, 3);
+        assert_eq!(stats.deleted_doc_count, 2);
+
+        let result = engine.merge_segments().unwrap();
+        assert_eq!(result.segments_before, stats.segment_count);
+        assert_eq!(result.segments_after, 1);
+
+        let stats = engine.get_index_stats(&[]).unwrap();
+        assert_eq!((stats.segment_count, stats.deleted_doc_count), (1, 0));
+        assert_eq!(stats.document_count, 3);
+        assert_eq!(engine.search_launcher("file", 10).unwrap().len(), 3);
+
+        // Nothing left to clean up right after a merge
+        assert_eq!(engine.garbage_collect().unwrap().files_removed, 0);
+    }
 
+    #[test]
+    fn test_verify_index() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
         let engine = create_test_engine();
         engine.index_folder(dir.path().to_str().unwrap()).unwrap();
-        
-        // Search for original content
-        let results = engine.search("original", 10).unwrap();
+
+        let root = dir.path().to_string_lossy().to_string();
+        let verification = engine.verify_index(std::slice::from_ref(&root)).unwrap();
+        assert!(verification.is_healthy);
+        assert_eq!(verification.documents_checked, 1);
+
+        let verification = engine.verify_index(&["/some/other/root".to_string()]).unwrap();
+        assert!(!verification.is_healthy);
+        assert_eq!(verification.outside_root_count, 1);
+        assert_eq!(verification.duplicate_path_count, 0);
+    }
+
+    #[test]
+    fn test_rebase_path() {
+        assert_eq!(rebase_path("D:\\Data\\work\\a.txt", "D:\\Data", "/mnt/data").as_deref(), Some("/mnt/data/work/a.txt"));
+        assert_eq!(rebase_path("d:\\data\\a.txt", "D:\\Data\\", "/mnt/data/").as_deref(), Some("/mnt/data/a.txt"));
+        assert_eq!(rebase_path("/home/me/docs/a.txt", "/home/me", "E:\\Backup").as_deref(), Some("E:\\Backup\\docs\\a.txt"));
+        assert_eq!(rebase_path("/home/me", "/home/me", "/srv").as_deref(), Some("/srv"));
+        // Only whole components match, and Unix prefixes are case-sensitive
+        assert_eq!(rebase_path("/home/mel/a.txt", "/home/me", "/srv"), None);
+        assert_eq!(rebase_path("/Home/me/a.txt", "/home/me", "/srv"), None);
+    }
+
+    #[test]
+    fn test_snapshot_export_import_with_rebase() {
+        let old_root = tempdir().unwrap();
+        fs::write(old_root.path().join("notes.txt"), "snapshot content").unwrap();
+        fs::write(old_root.path().join("photo.jpg"), "not really a jpeg").unwrap();
+        let source = create_test_engine();
+        source.index_folder(old_root.path().to_str().unwrap()).unwrap();
+
+        let archive_dir = tempdir().unwrap();
+        let archive = archive_dir.path().join("index.tar");
+        let old_root_str = old_root.path().to_string_lossy().to_string();
+        source.record_crawl(true).unwrap();
+        let recent_queries = vec!["notes ext:txt".to_string()];
+        let manifest = source.export_snapshot(&archive, std::slice::from_ref(&old_root_str), &recent_queries).unwrap();
+        assert_eq!(manifest.schema_version, SCHEMA_VERSION);
+        assert_eq!(manifest.document_count, 2);
+
+        // The files live under a different root on the importing machine
+        let new_root = tempdir().unwrap();
+        fs::write(new_root.path().join("notes.txt"), "snapshot content").unwrap();
+        let new_root_str = new_root.path().to_string_lossy().to_string();
+
+        let target = create_test_engine();
+        let manifest = target.load_snapshot(&archive, Some((&old_root_str, &new_root_str))).unwrap();
+        assert_eq!(manifest.roots, vec![new_root_str.clone()]);
+        assert_eq!(manifest.recent_queries, recent_queries);
+        assert_eq!(manifest.crawl_times, source.crawl_times());
+        assert!(manifest.crawl_times.completed.is_some());
+        assert_eq!(target.get_document_count().unwrap(), 2);
+
+        let results = target.search_launcher("notes", 10).unwrap();
         assert_eq!(results.len(), 1);
-        
-        // Wait for at least 1 second to ensure mtime changes (stored in seconds)
-        thread::sleep(Duration::from_millis(1100));
-        File::create(&test_file).unwrap().write_all(b"modified content").unwrap();
-        
-        // Re-index (should detect the change)
+        assert_eq!(results[0].path, new_root.path().join("notes.txt").to_string_lossy());
+        assert_eq!(results[0].size, 0, "The other machine's metadata isn't carried over");
+        assert!(target.search("snapshot", 10).unwrap().is_empty());
+
+        // Reconciliation reads the local files and drops what doesn't exist locally
+        let local_files = collect_indexable_files(&new_root_str, &[]);
+        assert_eq!(target.index_files(&local_files).unwrap().added, 1);
+        target.commit_job_writes().unwrap();
+        assert_eq!(target.search("snapshot", 10).unwrap().len(), 1, "Text content is read from the new root");
+        assert_eq!(target.search_launcher("notes", 10).unwrap()[0].size, "snapshot content".len() as u64);
+        assert_eq!(target.purge_missing_files(&[new_root_str]).unwrap(), 1);
+
+        // Not a snapshot
+        let bogus = archive_dir.path().join("bogus.tar");
+        fs::write(&bogus, "definitely not a tar archive").unwrap();
+        assert!(create_test_engine().load_snapshot(&bogus, None).is_err());
+    }
+
+    #[test]
+    fn test_purge_missing_files() {
+        let dir = tempdir().unwrap();
+        let kept = dir.path().join("kept.txt");
+        let deleted = dir.path().join("deleted.txt");
+        File::create(&kept).unwrap().write_all(b"kept").unwrap();
+        File::create(&deleted).unwrap().write_all(b"deleted").unwrap();
+
+        let engine = create_test_engine();
         engine.index_folder(dir.path().to_str().unwrap()).unwrap();
-        
-        // Search for modified content
-        let results = engine.search("modified", 10).unwrap();
-        assert_eq!(results.len(), 1);
-        
-        // Original content should no longer be found
-        let results = engine.search("original", 10).unwrap();
-        assert_eq!(results.len(), 0);
+        assert_eq!(engine.get_document_count().unwrap(), 2);
+
+        fs::remove_file(&deleted).unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        // A root that's gone entirely (e.g. an unplugged drive) is left alone
+        assert_eq!(engine.purge_missing_files(&["/nonexistent/path/12345".to_string()]).unwrap(), 0);
+        assert_eq!(engine.purge_missing_files(std::slice::from_ref(&root)).unwrap(), 1);
+        assert_eq!(engine.get_document_count().unwrap(), 1);
+        assert_eq!(engine.search_launcher("kept", 10).unwrap().len(), 1);
+        assert!(engine.search_launcher("deleted", 10).unwrap().is_empty());
+
+        // Nothing left to purge
+        assert_eq!(engine.purge_missing_files(std::slice::from_ref(&root)).unwrap(), 0);
+
+        // A path indexed twice counts as one file
+        File::create(&deleted).unwrap().write_all(b"deleted").unwrap();
+        engine.index_file(deleted.to_str().unwrap()).unwrap();
+        let index = engine.get_index().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let term = Term::from_field_text(engine.path_field, deleted.to_str().unwrap());
+        let hits = searcher.search(&TermQuery::new(term, IndexRecordOption::Basic), &TopDocs::with_limit(1)).unwrap();
+        let doc: TantivyDocument = searcher.doc(hits[0].1).unwrap();
+        let mut writer: IndexWriter<TantivyDocument> = index.writer(50_000_000).unwrap();
+        writer.add_document(doc).unwrap();
+        writer.commit().unwrap();
+        drop(writer);
+        fs::remove_file(&deleted).unwrap();
+        assert_eq!(engine.purge_missing_files(&[root]).unwrap(), 1);
+        assert_eq!(engine.get_document_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_shadow_rebuild_swaps_atomically() {
+        let old_dir = tempdir().unwrap();
+        File::create(old_dir.path().join("old_notes.txt")).unwrap().write_all(b"old").unwrap();
+        let new_dir = tempdir().unwrap();
+        File::create(new_dir.path().join("new_notes.txt")).unwrap().write_all(b"new").unwrap();
+
+        let engine = create_test_engine();
+        engine.index_folder(old_dir.path().to_str().unwrap()).unwrap();
+
+        // A discarded rebuild leaves the live index alone
+        let shadow = engine.create_shadow().unwrap();
+        shadow.index_folder(new_dir.path().to_str().unwrap()).unwrap();
+        shadow.clear_index().unwrap();
+        assert_eq!(engine.search_launcher("old_notes", 10).unwrap().len(), 1);
+
+        // While a rebuild is in progress the live index keeps serving searches
+        let shadow = engine.create_shadow().unwrap();
+        shadow.index_folder(new_dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(engine.search_launcher("old_notes", 10).unwrap().len(), 1);
+        assert!(engine.search_launcher("new_notes", 10).unwrap().is_empty());
+
+        engine.swap_in_shadow(&shadow).unwrap();
+        assert!(engine.search_launcher("old_notes", 10).unwrap().is_empty());
+        assert_eq!(engine.search_launcher("new_notes", 10).unwrap().len(), 1);
+        assert!(!shadow.get_index_path().exists());
     }
 
     #[test]
@@ -1250,11 +4749,51 @@ mod tests {
         let engine = create_test_engine();
         engine.index_folder(dir.path().to_str().unwrap()).unwrap();
 
-        let stats = engine.get_index_stats().unwrap();
+        let stats = engine.get_index_stats(&[]).unwrap();
         assert_eq!(stats.document_count, 1);
         assert!(stats.size_bytes > 0);
     }
 
+    #[test]
+    fn test_index_stats_per_folder_and_extension() {
+        let dir = tempdir().unwrap();
+        let work = dir.path().join("work");
+        let nested = work.join("nested");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(work.join("a.md"), "12345").unwrap();
+        fs::write(work.join("b.md"), "123").unwrap();
+        fs::write(nested.join("c.txt"), "1").unwrap();
+        // Not UTF-8, so only its name gets indexed
+        fs::write(nested.join("latin1.txt"), b"caf\xe9").unwrap();
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let roots = vec![work.to_string_lossy().to_string(), nested.to_string_lossy().to_string()];
+        let stats = engine.get_index_stats(&roots).unwrap();
+        let folders: Vec<(u64, u64)> = stats.folders.iter().map(|f| (f.document_count, f.size_bytes)).collect();
+        assert_eq!(folders, vec![(2, 8), (2, 5)], "Files count towards the most specific root");
+        let extensions: Vec<(&str, u64)> = stats.top_extensions.iter().map(|e| (e.extension.as_str(), e.document_count)).collect();
+        assert_eq!(extensions, vec![("md", 2), ("txt", 2)]);
+        assert_eq!(stats.failed_file_count, 1);
+        assert_eq!(engine.search_launcher("latin1", 10).unwrap().len(), 1);
+
+        // Unchanged failed files aren't read again; fixing one clears the failure
+        assert_eq!(engine.index_files(&[nested.join("latin1.txt")]).unwrap(), IndexChanges::default());
+        fs::write(nested.join("latin1.txt"), "cafe").unwrap();
+        engine.index_files(&[nested.join("latin1.txt")]).unwrap();
+        engine.commit_job_writes().unwrap();
+        assert_eq!(engine.get_index_stats(&roots).unwrap().failed_file_count, 0);
+
+        // Crawl times are kept beside the index, so they outlive the engine
+        assert_eq!(stats.last_crawl_started, None);
+        engine.record_crawl(false).unwrap();
+        engine.record_crawl(true).unwrap();
+        let reopened = TantivyEngine::new_with_path(engine.index_path.clone()).unwrap();
+        let stats = reopened.get_index_stats(&roots).unwrap();
+        assert!(stats.last_crawl_started.is_some());
+        assert!(stats.last_indexed >= stats.last_crawl_started);
+    }
+
     #[test]
     fn test_clear_index() {
         let dir = tempdir().unwrap();
@@ -1379,6 +4918,220 @@ mod tests {
         let score = TantivyEngine::calculate_launcher_score(&parts, "7 rules of power.epub");
         assert!(score.is_none(), "Should not match 'xyz' in '7 rules of power.epub'");
     }
+
+    #[test]
+    fn test_parse_size_and_modified_filters() {
+        let filters = parse_search_query("report size:>10mb");
+        assert_eq!(filters.query, "report");
+        assert_eq!(filters.min_size, Some(10 * 1024 * 1024 + 1));
+        assert_eq!(filters.max_size, None);
+
+        let filters = parse_search_query("size:1kb..5mb");
+        assert_eq!(filters.min_size, Some(1024));
+        assert_eq!(filters.max_size, Some(5 * 1024 * 1024));
+
+        let filters = parse_search_query("modified:<7d");
+        let week_ago = chrono::Local::now().timestamp() - 7 * 86_400;
+        assert!((filters.modified_after.unwrap() - week_ago).abs() <= 1);
+        assert_eq!(filters.modified_before, None);
+
+        let filters = parse_search_query("modified:today");
+        let (after, before) = (filters.modified_after.unwrap(), filters.modified_before.unwrap());
+        let now = chrono::Local::now().timestamp();
+        assert!(after <= now && now <= before);
+
+        let filters = parse_search_query("modified:>2024-06-01");
+        let june_first = local_day_bounds(chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()).unwrap();
+        assert_eq!(filters.modified_after, Some(june_first.1 + 1));
+
+        // Invalid values are dropped rather than treated as query text
+        let filters = parse_search_query("size:huge modified:someday");
+        assert!(!filters.has_range_filters());
+        assert!(filters.query.is_empty());
+    }
+
+    #[test]
+    fn test_launcher_size_and_modified_filters() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("small.txt")).unwrap().write_all(b"tiny").unwrap();
+        File::create(dir.path().join("large.txt")).unwrap().write_all(&vec![b'a'; 4096]).unwrap();
+        let old_file = File::create(dir.path().join("old.txt")).unwrap();
+        old_file.set_modified(UNIX_EPOCH + Duration::from_secs(1_600_000_000)).unwrap();
+
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        // Filter-only query resolved through the range query
+        let results = engine.search_launcher("size:>2kb", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_name, "large.txt");
+
+        // Combined with text matching
+        let results = engine.search_launcher("txt size:..1kb", 10).unwrap();
+        let names: Vec<_> = results.iter().map(|r| r.file_name.as_str()).collect();
+        assert!(names.contains(&"small.txt") && names.contains(&"old.txt"));
+        assert!(!names.contains(&"large.txt"));
+
+        let results = engine.search_launcher("modified:<2021-01-01", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_name, "old.txt");
+
+        let results = engine.search_launcher("modified:<7d", 10).unwrap();
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_filename_match_positions() {
+        // Contiguous substring is preferred over the earliest scattered chars
+        assert_eq!(filename_match_positions(&["vib"], "v_archived_vibes.txt"), vec![11, 12, 13]);
+        // Scattered launcher-style match
+        assert_eq!(filename_match_positions(&["7r"], "7 Rules of Power.epub"), vec![0, 2]);
+        // Parts match in order, case-insensitively, by char index
+        assert_eq!(filename_match_positions(&["氛围", "EPUB"], "氛围.epub"), vec![0, 1, 3, 4, 5, 6]);
+        assert!(filename_match_positions(&["xyz"], "report.pdf").is_empty());
+    }
+
+    #[test]
+    fn test_search_result_metadata() {
+        let dir = tempdir().unwrap();
+        let test_file = dir.path().join("Quarterly Report.MD");
+        File::create(&test_file).unwrap().write_all(b"revenue numbers").unwrap();
+
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+        engine.index_browser_data(vec![crate::services::browser_extractor::BrowserData {
+            url: "https://example.com/report".to_string(),
+            title: "Report portal".to_string(),
+            source: "Chrome (Default)".to_string(),
+            data_type: "Bookmark".to_string(),
+        }]).unwrap();
+
+        let results = engine.search_launcher("report in:files", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        let file = &results[0];
+        assert_eq!(file.extension, "md");
+        assert_eq!(file.size, 15);
+        assert!(file.modified_time > 0);
+        assert_eq!(file.url, None);
+        assert_eq!(file.source, None);
+        assert_eq!(file.match_positions, vec![10, 11, 12, 13, 14, 15]);
+
+        let results = engine.search_launcher("report in:bookmarks", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        let bookmark = &results[0];
+        assert_eq!(bookmark.url.as_deref(), Some("https://example.com/report"));
+        assert_eq!(bookmark.source.as_deref(), Some("Chrome (Default)"));
+        assert_eq!(bookmark.extension, "");
+        assert_eq!(bookmark.match_positions, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_launcher_cancellation_and_content_phase() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("budget.txt")).unwrap().write_all(b"quarterly numbers").unwrap();
+        File::create(dir.path().join("notes.md")).unwrap().write_all(b"see the budget spreadsheet").unwrap();
+
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let cancelled = AtomicBool::new(true);
+        let result = engine.search_launcher_cancellable(parse_search_query("budget"), 10, &cancelled, None).unwrap();
+        assert!(result.is_none(), "Cancelled search should not return results");
+
+        let cancelled = AtomicBool::new(false);
+        let result = engine.search_launcher_cancellable(parse_search_query("budget"), 10, &cancelled, None).unwrap();
+        assert_eq!(result.unwrap().len(), 1);
+
+        // Content phase finds the file that only mentions the term, and respects filters
+        let content = engine.search_content(&parse_search_query("budget"), 10).unwrap();
+        assert!(content.iter().any(|r| r.file_name == "notes.md"));
+        let content = engine.search_content(&parse_search_query("budget ext:txt"), 10).unwrap();
+        assert!(content.iter().all(|r| r.extension == "txt"));
+    }
+
+    #[test]
+    fn test_parse_sort_mode() {
+        let filters = parse_search_query("report sort:modified");
+        assert_eq!(filters.query, "report");
+        assert_eq!(filters.sort, SortMode { field: SortField::Modified, descending: true });
+
+        assert_eq!(SortMode::parse("size-asc"), Some(SortMode { field: SortField::Size, descending: false }));
+        assert_eq!(SortMode::parse("name"), Some(SortMode { field: SortField::Name, descending: false }));
+        assert_eq!(SortMode::parse("name-desc"), Some(SortMode { field: SortField::Name, descending: true }));
+        assert_eq!(SortMode::parse("color"), None);
+        assert_eq!(parse_search_query("report").sort, SortMode { field: SortField::Score, descending: true });
+    }
+
+    #[test]
+    fn test_launcher_sort_modes() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("b_notes.txt")).unwrap().write_all(&[b'a'; 300]).unwrap();
+        File::create(dir.path().join("a_notes.txt")).unwrap().write_all(&[b'a'; 200]).unwrap();
+        File::create(dir.path().join("c_notes.txt")).unwrap().write_all(&[b'a'; 100]).unwrap();
+        let old_file = File::options().write(true).open(dir.path().join("b_notes.txt")).unwrap();
+        old_file.set_modified(UNIX_EPOCH + Duration::from_secs(1_600_000_000)).unwrap();
+
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let names = |query: &str| -> Vec<String> {
+            engine.search_launcher(query, 10).unwrap().into_iter().map(|r| r.file_name).collect()
+        };
+
+        assert_eq!(names("notes sort:name"), vec!["a_notes.txt", "b_notes.txt", "c_notes.txt"]);
+        assert_eq!(names("notes sort:name-desc"), vec!["c_notes.txt", "b_notes.txt", "a_notes.txt"]);
+        assert_eq!(names("notes sort:size"), vec!["b_notes.txt", "a_notes.txt", "c_notes.txt"]);
+        assert_eq!(names("notes sort:size-asc"), vec!["c_notes.txt", "a_notes.txt", "b_notes.txt"]);
+        assert_eq!(names("notes sort:modified").last().unwrap(), "b_notes.txt");
+    }
+
+    #[test]
+    fn test_resolve_folder_scopes() {
+        let mut filters = parse_search_query("report under:Work path:/tmp/other/");
+        assert_eq!(filters.query, "report");
+        assert_eq!(filters.paths, vec!["Work", "/tmp/other"]);
+
+        let indexed_folders = vec!["D:\\Data\\work".to_string(), "/home/me/personal".to_string()];
+        filters.resolve_folder_scopes(&indexed_folders);
+        assert_eq!(filters.paths, vec!["D:\\Data\\work", "/tmp/other"]);
+
+        assert!(matches_path_filter("d:/data/Work/report.pdf", &filters));
+        assert!(matches_path_filter("/tmp/other/report.pdf", &filters));
+        assert!(!matches_path_filter("/tmp/other2/report.pdf", &filters));
+        assert!(!matches_path_filter("/home/me/personal/report.pdf", &filters));
+    }
+
+    #[test]
+    fn test_launcher_path_query_and_scope() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("proj").join("src");
+        let docs = dir.path().join("docs");
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&docs).unwrap();
+        File::create(src.join("main.rs")).unwrap().write_all(b"fn main() {}").unwrap();
+        File::create(docs.join("main.md")).unwrap().write_all(b"# main").unwrap();
+
+        let engine = create_test_engine();
+        engine.index_folder(dir.path().to_str().unwrap()).unwrap();
+
+        let results = engine.search_launcher("main", 10).unwrap();
+        assert_eq!(results.len(), 2);
+
+        // Separator in the query matches directory components
+        let results = engine.search_launcher("proj/src main", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_name, "main.rs");
+
+        // Scope by full path or by a relative directory name
+        let query = format!("main path:{}", docs.to_string_lossy());
+        let results = engine.search_launcher(&query, 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_name, "main.md");
+
+        let results = engine.search_launcher("under:src", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_name, "main.rs");
+    }
 }
 
 // ============================================================================
@@ -1392,23 +5145,14 @@ static APP_ENGINE: Lazy<Mutex<TantivyEngine>> = Lazy::new(|| {
     Mutex::new(TantivyEngine::new().expect("Failed to create TantivyEngine"))
 });
 
+/// Read-only shared indexes searched alongside the active one, by label
+static SHARED_ENGINES: Lazy<Mutex<Vec<(String, TantivyEngine)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
 pub fn init() -> tantivy::Result<()> {
     let _unused = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
     Ok(())
 }
 
-pub fn index_folder(folder: &str) -> tantivy::Result<()> {
-    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
-    engine.index_folder(folder)?;
-    Ok(())
-}
-
-pub fn index_browser_data(data: Vec<crate::services::browser_extractor::BrowserData>) -> tantivy::Result<()> {
-    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
-    engine.index_browser_data(data)?;
-    Ok(())
-}
-
 pub fn search_files(query: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
     let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
     engine.search(query, limit)
@@ -1456,19 +5200,110 @@ pub fn delete_folder(folder: &str) -> tantivy::Result<u32> {
         assert!(!results.is_empty(), "Should find by extension");
     }
 
+/// Opens a shared index read-only and includes it in searches under the given label
+///
+/// Replaces a shared index already open under the same label.
+pub fn open_shared_index(label: &str, index_path: &Path) -> tantivy::Result<()> {
+    let engine = TantivyEngine::open_read_only(index_path.to_path_buf())?;
+    let mut shared = SHARED_ENGINES.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    shared.retain(|(existing, _)| existing != label);
+    shared.push((label.to_string(), engine));
+    Ok(())
+}
+
+/// Stops searching a shared index
+pub fn close_shared_index(label: &str) -> tantivy::Result<()> {
+    let mut shared = SHARED_ENGINES.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    shared.retain(|(existing, _)| existing != label);
+    Ok(())
+}
+
+/// Runs a search against every shared index, labelling results with their source
+///
+/// A shared index that fails (e.g. on an unreachable network drive) is skipped, so it
+/// never breaks searching the personal index.
+pub fn search_shared_indexes(
+    run: impl Fn(&TantivyEngine) -> tantivy::Result<Vec<SearchResult>>,
+) -> tantivy::Result<Vec<SearchResult>> {
+    let shared = SHARED_ENGINES.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    let mut results = Vec::new();
+    for (label, engine) in shared.iter() {
+        match run(engine) {
+            Ok(hits) => results.extend(hits.into_iter().map(|mut hit| {
+                hit.shared_index = Some(label.clone());
+                hit
+            })),
+            Err(e) => eprintln!("Failed to search shared index '{}': {}", label, e),
+        }
+    }
+    Ok(results)
+}
+
+/// Adds shared index results to the active index's results and merges them
+fn with_shared_results(
+    results: Vec<SearchResult>,
+    filters: &SearchFilters,
+    limit: usize,
+    run: impl Fn(&TantivyEngine) -> tantivy::Result<Vec<SearchResult>>,
+) -> tantivy::Result<Vec<SearchResult>> {
+    let shared_results = search_shared_indexes(run)?;
+    if shared_results.is_empty() {
+        return Ok(results);
+    }
+    let mut merged = results;
+    merged.extend(shared_results);
+    Ok(merge_results(merged, &filters.sort, limit))
+}
+
+/// Replaces the global engine with one over the given workspace's index
+pub fn switch_workspace(name: &str, exclusions: Vec<String>) -> tantivy::Result<()> {
+    let mut workspace_engine = TantivyEngine::for_workspace(name)?;
+    workspace_engine.set_exclusions(exclusions);
+    let mut engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    *engine = workspace_engine;
+    Ok(())
+}
+
+/// Updates the exclusions of the active workspace and drops newly excluded files
+pub fn set_exclusions(exclusions: Vec<String>) -> tantivy::Result<u32> {
+    let mut engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.set_exclusions(exclusions);
+    engine.purge_excluded_files()
+}
+
+/// Deletes the index directory of an (inactive) workspace
+pub fn delete_workspace_index(name: &str) -> tantivy::Result<()> {
+    let index_path = workspace_index_path(name);
+    // Non-default workspaces own the whole directory around their index
+    let workspace_dir = match index_path.parent() {
+        Some(parent) if !name.eq_ignore_ascii_case(DEFAULT_WORKSPACE) => parent.to_path_buf(),
+        _ => index_path,
+    };
+    if workspace_dir.exists() {
+        fs::remove_dir_all(&workspace_dir)?;
+    }
+    Ok(())
+}
+
 pub fn clear_index() -> tantivy::Result<()> {
     let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
     engine.clear_index()?;
     Ok(())
 }
 
-pub fn rebuild_index(folders: &[String]) -> tantivy::Result<()> {
+/// Creates an engine over an empty shadow index for a rebuild
+///
+/// The shadow engine is used directly, without the global lock, so searches keep
+/// running against the live index while it is filled.
+pub fn create_shadow_engine() -> tantivy::Result<TantivyEngine> {
     let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
-    engine.clear_index()?;
-    for folder in folders {
-        engine.index_folder(folder)?;
-    }
-    Ok(())
+    engine.create_shadow()
+}
+
+/// Swaps a completed shadow index in place of the live index
+pub fn swap_in_shadow(shadow: &TantivyEngine) -> tantivy::Result<()> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.swap_in_shadow(shadow)
 }
 
 pub fn get_document_count() -> tantivy::Result<u64> {
@@ -1476,9 +5311,103 @@ pub fn get_document_count() -> tantivy::Result<u64> {
     engine.get_document_count()
 }
 
-pub fn get_index_stats() -> tantivy::Result<IndexStats> {
+pub fn get_index_stats(roots: &[String]) -> tantivy::Result<IndexStats> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.get_index_stats(roots)
+}
+
+/// Records the start or completion of a crawl of the active workspace's folders
+pub fn record_crawl(completed: bool) -> tantivy::Result<()> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.record_crawl(completed)
+}
+
+/// Indexes a batch of a background job's files, committed on a time or size basis
+pub fn index_files(paths: &[PathBuf]) -> tantivy::Result<IndexChanges> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.index_files(paths)
+}
+
+/// Commits the writes a background job left uncommitted
+pub fn commit_job_writes() -> tantivy::Result<()> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.commit_job_writes()
+}
+
+/// Exports the index and a manifest into a single archive
+pub fn export_snapshot(archive_path: &Path, roots: &[String], recent_queries: &[String]) -> tantivy::Result<SnapshotManifest> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.export_snapshot(archive_path, roots, recent_queries)
+}
+
+/// Imports a snapshot archive in place of the live index
+///
+/// The archive is unpacked (and rebased) into a shadow index first, so the live index
+/// is only replaced once the snapshot turned out to be valid. The snapshot's crawl
+/// times replace the live index's, as they describe the index now in use.
+pub fn import_snapshot(archive_path: &Path, rebase: Option<(&str, &str)>) -> tantivy::Result<SnapshotManifest> {
+    let shadow = create_shadow_engine()?;
+    let loaded = shadow.load_snapshot(archive_path, rebase);
+    let swapped = match &loaded {
+        Ok(_) => swap_in_shadow(&shadow),
+        Err(_) => Ok(()),
+    };
+    if loaded.is_err() || swapped.is_err() {
+        let _ = shadow.clear_index();
+    }
+    swapped?;
+    let manifest = loaded?;
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.set_crawl_times(manifest.crawl_times)?;
+    Ok(manifest)
+}
+
+/// Merges all index segments into one
+pub fn merge_segments() -> tantivy::Result<MaintenanceResult> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.merge_segments()
+}
+
+/// Deletes index files that are no longer used
+pub fn garbage_collect() -> tantivy::Result<MaintenanceResult> {
     let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
-    engine.get_index_stats()
+    engine.garbage_collect()
+}
+
+/// Checks that indexed paths are unique and under the given roots
+pub fn verify_index(roots: &[String]) -> tantivy::Result<IndexVerification> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.verify_index(roots)
+}
+
+/// Indexed documents similar to the one at `path` (files, bookmarks and history)
+pub fn related(path: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.related(path, limit)
+}
+
+/// "Did you mean" suggestions for a query against the active index
+pub fn suggest_spelling(query: &str, limit: usize) -> tantivy::Result<Vec<String>> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.suggest_spelling(query, limit)
+}
+
+/// Index terms completing a prefix, from the active index
+pub fn complete_prefix(prefix: &str, limit: usize) -> tantivy::Result<TermCompletions> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.complete_prefix(prefix, limit)
+}
+
+/// Groups of indexed files with identical content
+pub fn find_duplicates() -> tantivy::Result<Vec<DuplicateGroup>> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.find_duplicates()
+}
+
+/// Removes indexed files under the given roots that are gone from disk
+pub fn purge_missing_files(roots: &[String]) -> tantivy::Result<u32> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    engine.purge_missing_files(roots)
 }
 
 /// Indexes a single file by path (used by file watcher)
@@ -1487,10 +5416,96 @@ pub fn index_single_file(path: &str) -> tantivy::Result<bool> {
     engine.index_file(path)
 }
 
-/// Launcher-style search (characters in sequence, like "7r" → "7 Rules")
-pub fn search_files_launcher(query: &str, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
+/// Cancellable launcher-style search; returns None if cancelled before finishing
+///
+/// Includes results from the shared indexes. All their hits are added to `facets`.
+pub fn search_files_launcher_cancellable(
+    filters: SearchFilters,
+    limit: usize,
+    cancelled: &AtomicBool,
+    facets: &mut FacetCounter,
+) -> tantivy::Result<Option<Vec<SearchResult>>> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    let Some(results) = engine.search_launcher_cancellable(filters.clone(), limit, cancelled, Some(&mut *facets))? else {
+        return Ok(None);
+    };
+    drop(engine);
+
+    let facets = std::cell::RefCell::new(facets);
+    let merged = with_shared_results(results, &filters, limit, |shared| {
+        let mut facets = facets.borrow_mut();
+        Ok(shared.search_launcher_cancellable(filters.clone(), limit, cancelled, Some(&mut **facets))?.unwrap_or_default())
+    })?;
+    Ok((!cancelled.load(Ordering::SeqCst)).then_some(merged))
+}
+
+/// Cancellable content (BM25) search restricted by launcher filters, including the shared indexes
+///
+/// Returns None if cancelled. The engine lock is only held for the index search; the
+/// files are re-read for their matching lines after it is released.
+pub fn search_files_content(filters: &SearchFilters, limit: usize, cancelled: &AtomicBool) -> tantivy::Result<Option<Vec<SearchResult>>> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    let Some(results) = engine.search_content_cancellable(filters, limit, cancelled)? else {
+        return Ok(None);
+    };
+    let terms = engine.line_match_terms(&filters.query, &filters.synonyms);
+    drop(engine);
+
+    let mut merged = with_shared_results(results, filters, limit, |shared| {
+        Ok(shared.search_content_cancellable(filters, limit, cancelled)?.unwrap_or_default())
+    })?;
+    if cancelled.load(Ordering::SeqCst) {
+        return Ok(None);
+    }
+    add_line_matches(&mut merged, &terms);
+    Ok(Some(merged))
+}
+
+/// Terms whose lines are listed as `line_matches` of content hits for `query`
+///
+/// For content hits that didn't come through `search_files_content`, e.g. from other workspaces.
+pub fn line_match_terms(query: &str, synonyms: &[Vec<String>]) -> tantivy::Result<Vec<String>> {
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    Ok(engine.line_match_terms(query, synonyms))
+}
+
+/// Regex (`re:`) search over the text files of the active and shared indexes, sending
+/// matching files as they are found
+///
+/// Candidates are narrowed with the index under the engine lock; the files are then
+/// scanned without holding it, so other searches aren't blocked by the disk reads.
+/// Shared index files are only found where their paths exist on this machine.
+/// Other workspaces (`ws:`) aren't scanned, so that combination is rejected.
+pub fn grep_files(
+    filters: &SearchFilters,
+    limit: usize,
+    cancelled: &AtomicBool,
+    sender: &Sender<SearchResult>,
+) -> tantivy::Result<RegexScanSummary> {
+    if !filters.workspaces.is_empty() {
+        return Err(tantivy::TantivyError::InvalidArgument(
+            "re: and grep: search the active workspace only and can't be combined with ws:".to_string(),
+        ));
+    }
+    let pattern = filters.regex.as_deref().unwrap_or_default();
+    let regex = compile_search_regex(pattern)?;
+    let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
+    let mut candidates = engine.regex_candidates(filters, pattern)?;
+    drop(engine);
+
+    let mut paths: std::collections::HashSet<String> = candidates.iter().map(|c| c.path.clone()).collect();
+    let shared = search_shared_indexes(|shared| shared.regex_candidates(filters, pattern))?;
+    candidates.extend(shared.into_iter().filter(|c| paths.insert(c.path.clone())));
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.modified_time));
+    Ok(scan_files_for_regex(&candidates, &regex, limit, REGEX_SEARCH_TIMEOUT, cancelled, sender))
+}
+
+/// Launcher-style search with filters already parsed by the caller, including the shared indexes
+pub fn search_files_launcher_with_filters(filters: SearchFilters, limit: usize) -> tantivy::Result<Vec<SearchResult>> {
     let engine = APP_ENGINE.lock().map_err(|e| tantivy::TantivyError::InternalError(e.to_string()))?;
-    engine.search_launcher(query, limit)
+    let results = engine.search_launcher_with_filters(filters.clone(), limit)?;
+    drop(engine);
+    with_shared_results(results, &filters, limit, |shared| shared.search_launcher_with_filters(filters.clone(), limit))
 }
 
 pub fn get_supported_extensions() -> SupportedExtensions {
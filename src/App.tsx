@@ -1,7 +1,10 @@
-import { useState, useCallback } from 'react';
+import { useState, useCallback, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import SearchBar from './components/SearchBar';
+import type { QuerySuggestion } from './components/SearchBar';
 import ResultsList from './components/ResultsList';
+import type { SearchFacets } from './components/ResultsList';
 import Settings from './components/Settings';
 import Help from './components/Help';
 
@@ -10,6 +13,27 @@ interface SearchResult {
   file_name: string;
   score: number;
   record_type?: string;
+  url?: string | null;
+  extension?: string;
+  source?: string | null;
+  size?: number;
+  modified_time?: number;
+  match_positions?: number[];
+  workspace?: string | null;
+  shared_index?: string | null;
+  line?: number | null;
+  symbol_kind?: string | null;
+  line_matches?: { line: number; text: string; match_positions: number[] }[];
+}
+
+interface SearchEvent {
+  search_id: number;
+  phase: 'filename' | 'content' | 'regex';
+  results: SearchResult[];
+  done: boolean;
+  error?: string | null;
+  suggestions?: string[];
+  facets?: SearchFacets | null; // On the last event of a search
 }
 
 type TabType = 'search' | 'settings' | 'help';
@@ -20,30 +44,110 @@ function App() {
   const [selectedIndex, setSelectedIndex] = useState(0);
   const [activeTab, setActiveTab] = useState<TabType>('search');
   const [isLoading, setIsLoading] = useState(false);
+  const [suggestions, setSuggestions] = useState<string[]>([]);
+  const [facets, setFacets] = useState<SearchFacets | null>(null);
+  const [completions, setCompletions] = useState<QuerySuggestion[]>([]);
+
+  // Newest search session; events from older sessions are ignored
+  const activeSearchId = useRef(0);
+  // Sessions up to this ID were dropped (cleared or replaced by related results)
+  const cancelledUpTo = useRef(0);
+  // Counts start_search calls, so a call that resolves after a newer one knows it is stale
+  const searchRequest = useRef(0);
+  // Search session the shown results came from; its first event replaces them
+  const resultsSearchId = useRef(0);
+  // Counts suggest calls, so only the newest one's completions are shown
+  const suggestRequest = useRef(0);
+
+  useEffect(() => {
+    const unlisten = listen<SearchEvent>('search-results', (event) => {
+      const { search_id, phase, results: batch, done, error, suggestions: didYouMean, facets: counts } = event.payload;
+      // IDs only grow, so a higher ID is a newer search whose start_search call hasn't resolved yet
+      if (search_id <= cancelledUpTo.current || search_id < activeSearchId.current) return;
+      activeSearchId.current = search_id;
+
+      // Regex searches send several batches, none of them a 'filename' phase
+      if (phase === 'filename' || search_id !== resultsSearchId.current) {
+        resultsSearchId.current = search_id;
+        setResults(batch);
+        setSelectedIndex(0);
+      } else {
+        setResults((prev) => [...prev, ...batch]);
+      }
+      if (error) {
+        console.error('Search failed:', error);
+      }
+      if (done) {
+        setSuggestions(didYouMean ?? []);
+        setFacets(counts ?? null);
+        setIsLoading(false);
+      }
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  // Drops the running session; anything it still sends is ignored
+  const dropActiveSearch = useCallback(() => {
+    searchRequest.current += 1;
+    cancelledUpTo.current = activeSearchId.current;
+    invoke('cancel_search', { searchId: activeSearchId.current }).catch(() => {});
+  }, []);
 
   const handleSearch = useCallback(async (searchQuery: string) => {
     setQuery(searchQuery);
+    setSuggestions([]);
+    setFacets(null);
+    const suggestion = ++suggestRequest.current;
     if (searchQuery.trim() === '') {
+      dropActiveSearch();
       setResults([]);
+      setCompletions([]);
+      setIsLoading(false);
       return;
     }
+    invoke<QuerySuggestion[]>('suggest', { prefix: searchQuery, limit: 5 })
+      .then((found) => {
+        if (suggestion === suggestRequest.current) setCompletions(Array.isArray(found) ? found : []);
+      })
+      .catch(() => {
+        if (suggestion === suggestRequest.current) setCompletions([]);
+      });
     setIsLoading(true);
+    const request = ++searchRequest.current;
     try {
-      console.log('Searching for:', searchQuery);
-      const searchResults = await invoke<SearchResult[]>('search', {
+      // Results arrive as 'search-results' events; starting a new search supersedes the previous one
+      const searchId = await invoke<number>('start_search', {
         query: searchQuery,
         limit: 20,
       });
-      console.log('Search results:', searchResults);
-      setResults(searchResults);
-      setSelectedIndex(0);
+      if (request !== searchRequest.current) {
+        // Cleared or superseded before its ID was known
+        cancelledUpTo.current = Math.max(cancelledUpTo.current, searchId);
+        invoke('cancel_search', { searchId }).catch(() => {});
+        return;
+      }
+      activeSearchId.current = Math.max(activeSearchId.current, searchId);
     } catch (error) {
       console.error('Search failed:', error);
       alert('Search failed: ' + error);
+      setIsLoading(false);
+    }
+  }, [dropActiveSearch]);
+
+  const showRelated = useCallback(async (path: string) => {
+    dropActiveSearch();
+    try {
+      setResults(await invoke<SearchResult[]>('related', { path, limit: 20 }));
+      setSelectedIndex(0);
+      setFacets(null);
+    } catch (error) {
+      console.error('Failed to find related documents:', error);
     } finally {
       setIsLoading(false);
     }
-  }, []);
+  }, [dropActiveSearch]);
 
   const handleKeyDown = useCallback((e: React.KeyboardEvent) => {
     if (activeTab !== 'search') return;
@@ -59,11 +163,24 @@ function App() {
         break;
       case 'Enter':
         if (results[selectedIndex]) {
-          openFile(results[selectedIndex].path);
+          handleResultSelect(results[selectedIndex]);
+        }
+        break;
+      case 'Tab':
+        if (completions[0] && completions[0].text !== query) {
+          e.preventDefault();
+          handleSearch(completions[0].text);
+        }
+        break;
+      case 'r':
+        // Ctrl+R: replace the results with documents similar to the selected one
+        if (e.ctrlKey && results[selectedIndex]) {
+          e.preventDefault();
+          showRelated(results[selectedIndex].path);
         }
         break;
     }
-  }, [results, selectedIndex, activeTab]);
+  }, [results, selectedIndex, activeTab, showRelated, completions, query, handleSearch]);
 
   const handleResultSelect = async (result: SearchResult) => {
     try {
@@ -72,28 +189,15 @@ function App() {
         const { open } = await import('@tauri-apps/plugin-shell');
         await open(result.path);
       } else {
-        await invoke('open_file', { path: result.path });
+        // Symbols open at their definition, content hits at the first matching line
+        const line = result.line ?? result.line_matches?.[0]?.line ?? null;
+        await invoke('open_file', { path: result.path, line });
       }
     } catch (error) {
       console.error('Failed to open item:', error);
     }
   };
 
-  const openFile = async (path: string) => {
-    // Legacy call wrapper - try to find result object or just generic open
-    const result = results.find(r => r.path === path);
-    if (result) {
-      handleResultSelect(result);
-    } else {
-      // Fallback
-      try {
-        await invoke('open_file', { path });
-      } catch (error) {
-        console.error('Failed to open file:', error);
-      }
-    }
-  };
-
   return (
     <div className="container">
       <div className="search-container">
@@ -124,12 +228,17 @@ function App() {
               query={query}
               onChange={handleSearch}
               onKeyDown={handleKeyDown}
+              completions={completions}
             />
             <ResultsList
               results={results}
               selectedIndex={selectedIndex}
               isLoading={isLoading}
               onSelect={handleResultSelect}
+              suggestions={suggestions}
+              onSuggestion={handleSearch}
+              facets={facets}
+              onRefine={(filter) => handleSearch(`${query.trim()} ${filter}`)}
             />
           </>
         )}
@@ -111,6 +111,167 @@ function Help() {
                 </div>
             </section>
 
+            <section className="help-section">
+                <h3>📂 Filter by Folder</h3>
+                <p className="help-description">
+                    Use <code>under:</code> (or <code>path:</code>) with a full path or the name of an indexed folder.
+                    Words containing <code>/</code> match folder names in the path.
+                </p>
+                <div className="help-examples">
+                    <div className="help-example">
+                        <code>report under:work</code>
+                        <span>→ only files in the indexed "work" folder</span>
+                    </div>
+                    <div className="help-example">
+                        <code>path:D:\Data\Reports</code>
+                        <span>→ only files under that folder</span>
+                    </div>
+                    <div className="help-example">
+                        <code>proj/src main</code>
+                        <span>→ "main" files inside a proj/src folder</span>
+                    </div>
+                </div>
+            </section>
+
+            <section className="help-section">
+                <h3>📏 Filter by Size and Date</h3>
+                <p className="help-description">
+                    Use <code>size:</code> and <code>modified:</code> to narrow files by size or modified time.
+                </p>
+                <div className="help-examples">
+                    <div className="help-example">
+                        <code>size:&gt;10mb</code>
+                        <span>→ files larger than 10 MB</span>
+                    </div>
+                    <div className="help-example">
+                        <code>size:1kb..5mb</code>
+                        <span>→ files between 1 KB and 5 MB</span>
+                    </div>
+                    <div className="help-example">
+                        <code>modified:today</code>
+                        <span>→ files changed today</span>
+                    </div>
+                    <div className="help-example">
+                        <code>modified:&lt;7d</code>
+                        <span>→ files changed in the last 7 days</span>
+                    </div>
+                    <div className="help-example">
+                        <code>modified:&gt;2024-06-01</code>
+                        <span>→ files changed after June 1st, 2024</span>
+                    </div>
+                </div>
+            </section>
+
+            <section className="help-section">
+                <h3>👯 Find Duplicates</h3>
+                <p className="help-description">
+                    Use <code>dup:</code> to show only files whose content also exists elsewhere in your indexed folders.
+                </p>
+                <div className="help-examples">
+                    <div className="help-example">
+                        <code>report dup:</code>
+                        <span>→ copies of your reports</span>
+                    </div>
+                    <div className="help-example">
+                        <code>type:image dup:</code>
+                        <span>→ duplicated images</span>
+                    </div>
+                </div>
+            </section>
+
+            <section className="help-section">
+                <h3>🗂️ Search Other Workspaces</h3>
+                <p className="help-description">
+                    Use <code>ws:</code> to search other workspaces' indexes. Results are labelled with their workspace.
+                </p>
+                <div className="help-examples">
+                    <div className="help-example">
+                        <code>invoice ws:all</code>
+                        <span>→ every workspace</span>
+                    </div>
+                    <div className="help-example">
+                        <code>notes ws:work,home</code>
+                        <span>→ just the "work" and "home" workspaces</span>
+                    </div>
+                </div>
+            </section>
+
+            <section className="help-section">
+                <h3>🧩 Jump to Definitions</h3>
+                <p className="help-description">
+                    Use <code>sym:</code> to find functions, types and constants in source files. Opening one goes to its line in the editor set in Settings.
+                </p>
+                <div className="help-examples">
+                    <div className="help-example">
+                        <code>sym:parse_search_query</code>
+                        <span>→ where the function is defined</span>
+                    </div>
+                    <div className="help-example">
+                        <code>sym:config ext:ts</code>
+                        <span>→ definitions matching "config" in TypeScript files</span>
+                    </div>
+                </div>
+            </section>
+
+            <section className="help-section">
+                <h3>🔎 Regex Search</h3>
+                <p className="help-description">
+                    Use <code>re:</code> to find lines of text files matching a regular expression, or <code>grep:</code> to find lines containing some exact text. The value is one word; with <code>re:</code>, write <code>\s</code> for a space. Other filters still apply except <code>ws:</code>, and long scans stop after 10 seconds.
+                </p>
+                <div className="help-examples">
+                    <div className="help-example">
+                        <code>re:TODO\(\w+\)</code>
+                        <span>→ TODOs with an owner, like TODO(anna)</span>
+                    </div>
+                    <div className="help-example">
+                        <code>re:E\d{4} ext:log</code>
+                        <span>→ error codes in log files</span>
+                    </div>
+                    <div className="help-example">
+                        <code>grep:config.load( ext:rs</code>
+                        <span>→ the exact text, dot and bracket included</span>
+                    </div>
+                </div>
+            </section>
+
+            <section className="help-section">
+                <h3>🔁 Synonyms</h3>
+                <p className="help-description">
+                    Words grouped as synonyms in Settings find each other, in file names and in content. Direct matches still rank first.
+                </p>
+                <div className="help-examples">
+                    <div className="help-example">
+                        <code>k8s</code>
+                        <span>→ also finds "kubernetes" (with k8s ⇄ kubernetes)</span>
+                    </div>
+                    <div className="help-example">
+                        <code>报告</code>
+                        <span>→ also finds "report" (with 报告 ⇄ report)</span>
+                    </div>
+                </div>
+            </section>
+
+            <section className="help-section">
+                <h3>↕️ Sort Results</h3>
+                <p className="help-description">
+                    Use <code>sort:</code> to order results instead of by match quality. Add <code>-asc</code> or <code>-desc</code> to flip the order.
+                </p>
+                <div className="help-examples">
+                    <div className="help-example">
+                        <code>report sort:modified</code>
+                        <span>→ most recently changed first</span>
+                    </div>
+                    <div className="help-example">
+                        <code>type:video sort:size</code>
+                        <span>→ largest first</span>
+                    </div>
+                    <div className="help-example">
+                        <code>notes sort:name-desc</code>
+                        <span>→ Z to A</span>
+                    </div>
+                </div>
+            </section>
+
             <section className="help-section">
                 <h3>🚀 Pro Tips</h3>
                 <div className="help-tips">
@@ -122,6 +283,10 @@ function Help() {
                         <span className="tip-icon">⌨️</span>
                         <span>Use <strong>↑ ↓</strong> arrows to navigate, <strong>Enter</strong> to open</span>
                     </div>
+                    <div className="help-tip">
+                        <span className="tip-icon">🔗</span>
+                        <span>Press <strong>Ctrl+R</strong> to show files, bookmarks and history similar to the selected result</span>
+                    </div>
                     <div className="help-tip">
                         <span className="tip-icon">🎯</span>
                         <span>Shorter queries rank higher (denser matches)</span>
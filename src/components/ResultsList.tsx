@@ -3,6 +3,36 @@ interface SearchResult {
   file_name: string;
   score: number;
   record_type?: string; // "file", "history", "bookmark"
+  url?: string | null;
+  extension?: string;
+  source?: string | null; // Browser profile for history/bookmarks
+  size?: number;
+  modified_time?: number;
+  match_positions?: number[]; // Code point indices in file_name
+  workspace?: string | null; // Set for cross-workspace (ws:) searches
+  shared_index?: string | null; // Label of the shared index the result came from
+  line?: number | null; // Line of a symbol's definition
+  symbol_kind?: string | null; // "function", "struct", "class"... for symbols
+  line_matches?: LineMatch[]; // Matching lines, for content search hits
+}
+
+interface LineMatch {
+  line: number;
+  text: string;
+  match_positions: number[]; // Code point indices in text
+}
+
+interface FacetCount {
+  value: string;
+  count: number;
+  filter?: string | null; // Query operator narrowing to this value, e.g. "ext:pdf"
+}
+
+export interface SearchFacets {
+  record_types: FacetCount[];
+  extensions: FacetCount[];
+  types: FacetCount[];
+  folders: FacetCount[];
 }
 
 interface ResultsListProps {
@@ -10,9 +40,51 @@ interface ResultsListProps {
   selectedIndex: number;
   isLoading: boolean;
   onSelect: (result: SearchResult) => void;
+  suggestions?: string[]; // "Did you mean" queries when nothing was found
+  onSuggestion?: (query: string) => void;
+  facets?: SearchFacets | null; // Hit counts offered as refinements
+  onRefine?: (filter: string) => void;
+}
+
+const RECORD_TYPE_LABELS: Record<string, string> = {
+  file: 'Files',
+  Bookmark: 'Bookmarks',
+  History: 'History',
+  symbol: 'Symbols',
+};
+
+// One-click refinements such as "PDF (34) · Bookmarks (12)"
+function facetLinks(facets: SearchFacets, onRefine?: (filter: string) => void) {
+  const entries = [
+    // A single record type isn't a refinement
+    ...(facets.record_types.length > 1
+      ? facets.record_types.map((f) => ({ ...f, label: RECORD_TYPE_LABELS[f.value] ?? f.value, title: f.value }))
+      : []),
+    ...facets.types.map((f) => ({ ...f, label: f.value, title: `type:${f.value}` })),
+    ...facets.extensions.map((f) => ({ ...f, label: f.value.toUpperCase(), title: `.${f.value}` })),
+    ...facets.folders.map((f) => ({ ...f, label: f.value.split(/[\\/]/).pop() || f.value, title: f.value })),
+  ].filter((entry) => entry.filter);
+
+  return entries.map((entry, i) => (
+    <span key={`${entry.filter}`}>
+      {i > 0 && ' · '}
+      <a href="#" title={entry.title} onClick={(e) => { e.preventDefault(); onRefine?.(entry.filter as string); }}>
+        {entry.label} ({entry.count})
+      </a>
+    </span>
+  ));
 }
 
-function ResultsList({ results, selectedIndex, isLoading, onSelect }: ResultsListProps) {
+// Bold the characters the query matched
+function highlightName(name: string, positions?: number[]) {
+  if (!positions || positions.length === 0) {
+    return name;
+  }
+  const matched = new Set(positions);
+  return Array.from(name).map((ch, i) => (matched.has(i) ? <b key={i}>{ch}</b> : ch));
+}
+
+function ResultsList({ results, selectedIndex, isLoading, onSelect, suggestions, onSuggestion, facets, onRefine }: ResultsListProps) {
   if (isLoading) {
     return (
       <div className="empty-state">
@@ -25,22 +97,39 @@ function ResultsList({ results, selectedIndex, isLoading, onSelect }: ResultsLis
     return (
       <div className="empty-state">
         <p>No results found</p>
+        {suggestions && suggestions.length > 0 && (
+          <p>
+            Did you mean{' '}
+            {suggestions.map((suggestion, i) => (
+              <span key={suggestion}>
+                {i > 0 && ', '}
+                <a href="#" onClick={(e) => { e.preventDefault(); onSuggestion?.(suggestion); }}>
+                  {suggestion}
+                </a>
+              </span>
+            ))}
+            ?
+          </p>
+        )}
       </div>
     );
   }
 
   return (
     <div className="results-list">
+      {facets && results.length > 1 && (
+        <div className="result-facets">{facetLinks(facets, onRefine)}</div>
+      )}
       {results.map((result, index) => {
         const isUrl = result.record_type === 'history' || result.record_type === 'bookmark';
         return (
           <div
-            key={result.path}
+            key={`${result.path}:${result.line ?? ''}`}
             className={`result-item ${index === selectedIndex ? 'selected' : ''}`}
             onClick={() => onSelect(result)}
           >
             <div className="result-name">
-              {result.file_name}
+              {highlightName(result.file_name, result.match_positions)}
               {isUrl && (
                 <span style={{
                   fontSize: '10px',
@@ -53,10 +142,48 @@ function ResultsList({ results, selectedIndex, isLoading, onSelect }: ResultsLis
                   {result.record_type === 'bookmark' ? 'BOOKMARK' : 'HISTORY'}
                 </span>
               )}
+              {result.symbol_kind && (
+                <span style={{
+                  fontSize: '10px',
+                  marginLeft: '8px',
+                  padding: '2px 6px',
+                  borderRadius: '4px',
+                  backgroundColor: '#C9B6F2',
+                  color: '#333'
+                }}>
+                  {result.symbol_kind.toUpperCase()}
+                </span>
+              )}
+              {[result.workspace, result.shared_index].filter(Boolean).map((label) => (
+                <span key={label} style={{
+                  fontSize: '10px',
+                  marginLeft: '8px',
+                  padding: '2px 6px',
+                  borderRadius: '4px',
+                  backgroundColor: 'var(--bg-secondary)',
+                  color: 'var(--text-secondary)'
+                }}>
+                  {label}
+                </span>
+              ))}
             </div>
             <div className="result-path" style={{ color: isUrl ? '#4a9eff' : 'inherit' }}>
-              {result.path}
+              {result.path}{result.line ? `:${result.line}` : ''}
             </div>
+            {result.line_matches?.map((match) => (
+              <div
+                key={match.line}
+                className="result-line"
+                onClick={(e) => {
+                  // Open at this line rather than the first match
+                  e.stopPropagation();
+                  onSelect({ ...result, line: match.line });
+                }}
+              >
+                <span className="result-line-number">{match.line}</span>
+                {highlightName(match.text, match.match_positions)}
+              </div>
+            ))}
           </div>
         );
       })}
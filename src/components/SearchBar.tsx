@@ -1,23 +1,47 @@
+export interface QuerySuggestion {
+  text: string;
+  source: 'recent_query' | 'filename' | 'content';
+}
+
 interface SearchBarProps {
   query: string;
   onChange: (value: string) => void;
   onKeyDown: (e: React.KeyboardEvent) => void;
+  completions?: QuerySuggestion[]; // Tab accepts the first one
 }
 
-function SearchBar({ query, onChange, onKeyDown }: SearchBarProps) {
+const SOURCE_LABELS: Record<QuerySuggestion['source'], string> = {
+  recent_query: 'recent',
+  filename: 'file name',
+  content: 'content',
+};
+
+function SearchBar({ query, onChange, onKeyDown, completions = [] }: SearchBarProps) {
   return (
-    <div className="search-input-wrapper">
-      <span className="search-icon">⌕</span>
-      <input
-        type="text"
-        className="search-input"
-        placeholder="Search files..."
-        value={query}
-        onChange={(e) => onChange(e.target.value)}
-        onKeyDown={onKeyDown}
-        autoFocus
-      />
-    </div>
+    <>
+      <div className="search-input-wrapper">
+        <span className="search-icon">⌕</span>
+        <input
+          type="text"
+          className="search-input"
+          placeholder="Search files..."
+          value={query}
+          onChange={(e) => onChange(e.target.value)}
+          onKeyDown={onKeyDown}
+          autoFocus
+        />
+      </div>
+      {completions.length > 0 && (
+        <div className="search-completions">
+          {completions.map((completion) => (
+            <button key={completion.text} className="search-completion" onClick={() => onChange(completion.text)}>
+              {completion.text}
+              <span className="search-completion-source">{SOURCE_LABELS[completion.source]}</span>
+            </button>
+          ))}
+        </div>
+      )}
+    </>
   );
 }
 
@@ -1,5 +1,6 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 interface Config {
   indexed_folders: string[];
@@ -9,6 +10,24 @@ interface Config {
   };
   enable_history: boolean;
   enable_bookmarks: boolean;
+  excluded_folders: string[];
+  active_workspace: string;
+  shared_indexes: SharedIndex[];
+  synonyms: string[][];
+  editor_command?: string | null;
+}
+
+interface Workspace {
+  name: string;
+  indexed_folders: string[];
+  excluded_folders: string[];
+  enable_history: boolean;
+  enable_bookmarks: boolean;
+}
+
+interface SharedIndex {
+  label: string;
+  path: string;
 }
 
 interface SupportedExtensions {
@@ -24,8 +43,67 @@ interface IndexStats {
   file_count?: number;
   bookmark_count?: number;
   history_count?: number;
+  segment_count: number;
+  deleted_doc_count: number;
+  folders: { path: string; document_count: number; size_bytes: number }[];
+  top_extensions: { extension: string; document_count: number; size_bytes: number }[];
+  failed_file_count: number; // Indexed by name only, content unreadable
+  last_crawl_started?: number | null; // Unix seconds
+  last_indexed?: number | null; // Unix seconds, last completed crawl
+  watcher_running: boolean;
+  watched_folders: string[];
+}
+
+interface MaintenanceResult {
+  segments_before: number;
+  segments_after: number;
+  files_removed: number;
+}
+
+interface IndexVerification {
+  is_healthy: boolean;
+  documents_checked: number;
+  duplicate_path_count: number;
+  duplicate_paths: string[];
+  outside_root_count: number;
+  paths_outside_roots: string[];
+}
+
+interface DuplicateGroup {
+  hash: string;
+  size: number;
+  paths: string[];
+}
+
+type IndexJobState = 'queued' | 'scanning' | 'running' | 'paused' | 'completed' | 'cancelled' | 'failed';
+
+interface IndexJob {
+  id: number;
+  kind: 'add_folder' | 'reindex' | 'startup_scan' | 'import';
+  folders: string[];
+  state: IndexJobState;
+  files_total: number;
+  files_seen: number;
+  files_added: number;
+  files_updated: number;
+  files_removed: number;
+  current_path?: string | null;
+  error?: string | null;
 }
 
+const JOB_LABELS: Record<IndexJob['kind'], string> = {
+  add_folder: 'Indexing new folder',
+  reindex: 'Rebuilding index',
+  startup_scan: 'Checking for changes',
+  import: 'Reconciling imported index',
+};
+
+const formatJobChanges = (job: IndexJob) =>
+  `${job.files_added} added, ${job.files_updated} updated, ${job.files_removed} removed`;
+
+const isJobFinished = (job: IndexJob) =>
+  job.state === 'completed' || job.state === 'cancelled' || job.state === 'failed';
+
 function formatBytes(bytes: number, decimals = 2) {
   if (!+bytes) return '0 Bytes';
   const k = 1024;
@@ -35,27 +113,83 @@ function formatBytes(bytes: number, decimals = 2) {
   return `${parseFloat((bytes / Math.pow(k, i)).toFixed(dm))} ${sizes[i]}`;
 }
 
+// Unix seconds as a local date and time
+function formatTimestamp(seconds?: number | null) {
+  return seconds ? new Date(seconds * 1000).toLocaleString() : 'Never';
+}
+
 function Settings() {
   const [config, setConfig] = useState<Config>({
     indexed_folders: [],
     hotkey: { modifiers: ['Alt'], key: 'Space' },
     enable_history: false,
     enable_bookmarks: false,
+    excluded_folders: [],
+    active_workspace: 'default',
+    shared_indexes: [],
+    synonyms: [],
   });
   const [stats, setStats] = useState<IndexStats | null>(null);
   const [newFolder, setNewFolder] = useState('');
-  const [isReindexing, setIsReindexing] = useState(false);
+  const [newExclusion, setNewExclusion] = useState('');
+  const [workspaces, setWorkspaces] = useState<Workspace[]>([]);
+  const [newWorkspace, setNewWorkspace] = useState('');
+  const [sharedLabel, setSharedLabel] = useState('');
+  const [sharedPath, setSharedPath] = useState('');
+  const [newSynonyms, setNewSynonyms] = useState('');
+  const [jobs, setJobs] = useState<IndexJob[]>([]);
+  const [duplicates, setDuplicates] = useState<DuplicateGroup[] | null>(null);
+  const [maintenanceMessage, setMaintenanceMessage] = useState<string | null>(null);
+  const [snapshotPath, setSnapshotPath] = useState('');
+  const [rebaseFrom, setRebaseFrom] = useState('');
+  const [rebaseTo, setRebaseTo] = useState('');
   const [supportedExtensions, setSupportedExtensions] = useState<SupportedExtensions | null>(null);
 
   const [browserStatus, setBrowserStatus] = useState<{ installed_browsers: string[] } | null>(null);
 
   useEffect(() => {
     loadConfig();
+    loadWorkspaces();
     loadStats();
     loadBrowserStatus();
     loadSupportedExtensions();
+    loadJobs();
+
+    const unlisten = listen<IndexJob>('index-progress', (event) => {
+      const job = event.payload;
+      setJobs((prev) => {
+        const others = prev.filter((j) => j.id !== job.id);
+        return [...others, job].sort((a, b) => a.id - b.id);
+      });
+      if (isJobFinished(job)) {
+        loadStats();
+        if (job.state === 'failed') {
+          alert('Indexing failed: ' + job.error);
+        }
+      }
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
   }, []);
 
+  const loadJobs = async () => {
+    try {
+      const list = await invoke<IndexJob[]>('list_index_jobs');
+      setJobs(list ?? []);
+    } catch (error) {
+      console.error('Failed to load index jobs:', error);
+    }
+  };
+
+  const controlJob = async (command: 'pause_index_job' | 'resume_index_job' | 'cancel_index_job', jobId: number) => {
+    try {
+      await invoke(command, { jobId });
+    } catch (error) {
+      console.error(`Failed to ${command}:`, error);
+    }
+  };
+
   const loadSupportedExtensions = async () => {
     try {
       const exts = await invoke<SupportedExtensions>('get_supported_extensions');
@@ -85,6 +219,120 @@ function Settings() {
     }
   };
 
+  const loadWorkspaces = async () => {
+    try {
+      setWorkspaces((await invoke<Workspace[]>('list_workspaces')) ?? []);
+    } catch (error) {
+      console.error('Failed to load workspaces:', error);
+    }
+  };
+
+  const handleCreateWorkspace = async () => {
+    if (!newWorkspace.trim()) return;
+    try {
+      await invoke<Workspace>('create_workspace', { name: newWorkspace });
+      setNewWorkspace('');
+      await loadWorkspaces();
+    } catch (error) {
+      console.error('Failed to create workspace:', error);
+      alert('Failed to create workspace: ' + error);
+    }
+  };
+
+  const handleSwitchWorkspace = async (name: string) => {
+    try {
+      // The new workspace's folders are scanned in the background, reported as an index job
+      await invoke<number>('switch_workspace', { name });
+      await loadConfig();
+      await loadWorkspaces();
+      await loadStats();
+    } catch (error) {
+      console.error('Failed to switch workspace:', error);
+      alert('Failed to switch workspace: ' + error);
+    }
+  };
+
+  const handleDeleteWorkspace = async (name: string) => {
+    if (!confirm(`Delete workspace "${name}" and its index?`)) return;
+    try {
+      await invoke('delete_workspace', { name });
+      await loadWorkspaces();
+    } catch (error) {
+      console.error('Failed to delete workspace:', error);
+      alert('Failed to delete workspace: ' + error);
+    }
+  };
+
+  const handleAddSharedIndex = async () => {
+    if (!sharedLabel.trim() || !sharedPath.trim()) return;
+    try {
+      await invoke('add_shared_index', { label: sharedLabel, path: sharedPath });
+      setSharedLabel('');
+      setSharedPath('');
+      await loadConfig();
+    } catch (error) {
+      console.error('Failed to add shared index:', error);
+      alert('Failed to add shared index: ' + error);
+    }
+  };
+
+  const handleRemoveSharedIndex = async (label: string) => {
+    try {
+      await invoke('remove_shared_index', { label });
+      await loadConfig();
+    } catch (error) {
+      console.error('Failed to remove shared index:', error);
+      alert('Failed to remove shared index: ' + error);
+    }
+  };
+
+  const saveSynonyms = async (groups: string[][]) => {
+    try {
+      await invoke('set_synonyms', { groups });
+      await loadConfig();
+    } catch (error) {
+      console.error('Failed to update synonyms:', error);
+      alert('Failed to update synonyms: ' + error);
+    }
+  };
+
+  const handleAddSynonyms = async () => {
+    const group = newSynonyms.split(',').map(word => word.trim()).filter(word => word);
+    if (group.length < 2) return;
+    await saveSynonyms([...config.synonyms, group]);
+    setNewSynonyms('');
+  };
+
+  const handleSaveEditorCommand = async () => {
+    try {
+      await invoke('set_editor_command', { command: config.editor_command || null });
+      await loadConfig();
+    } catch (error) {
+      console.error('Failed to save editor command:', error);
+      alert('Failed to save editor command: ' + error);
+    }
+  };
+
+  const saveExclusions = async (folders: string[]) => {
+    try {
+      const removed = await invoke<number>('set_excluded_folders', { folders });
+      if (removed > 0) {
+        setMaintenanceMessage(`Removed ${removed} excluded files from the index`);
+      }
+      await loadConfig();
+      await loadStats();
+    } catch (error) {
+      console.error('Failed to update excluded folders:', error);
+      alert('Failed to update excluded folders: ' + error);
+    }
+  };
+
+  const handleAddExclusion = async () => {
+    if (!newExclusion.trim()) return;
+    await saveExclusions([...config.excluded_folders, newExclusion.trim()]);
+    setNewExclusion('');
+  };
+
   const loadStats = async () => {
     try {
       const s = await invoke<IndexStats>('get_index_stats');
@@ -98,11 +346,10 @@ function Settings() {
     if (!newFolder.trim()) return;
     try {
       console.log('Adding folder:', newFolder);
-      await invoke('add_folder', { path: newFolder });
+      // Indexing continues in the background; progress arrives as 'index-progress' events
+      await invoke<number>('add_folder', { path: newFolder });
       setNewFolder('');
       await loadConfig();
-      await loadStats();
-      alert('Folder added successfully!');
     } catch (error) {
       console.error('Failed to add folder:', error);
       alert('Failed to add folder: ' + error);
@@ -122,22 +369,138 @@ function Settings() {
   };
 
   const handleReindex = async () => {
-    setIsReindexing(true);
     try {
       console.log('Starting reindex...');
-      await invoke('reindex');
-      await loadStats();
-      alert('Reindex complete!');
+      await invoke<number>('reindex');
     } catch (error) {
       console.error('Failed to reindex:', error);
       alert('Failed to reindex: ' + error);
-    } finally {
-      setIsReindexing(false);
     }
   };
 
+  const handleMerge = async () => {
+    try {
+      const result = await invoke<MaintenanceResult>('merge_segments');
+      setMaintenanceMessage(`Merged ${result.segments_before} segments into ${result.segments_after}, removed ${result.files_removed} unused files`);
+      await loadStats();
+    } catch (error) {
+      console.error('Failed to merge segments:', error);
+      alert('Failed to merge segments: ' + error);
+    }
+  };
+
+  const handleGarbageCollect = async () => {
+    try {
+      const result = await invoke<MaintenanceResult>('garbage_collect');
+      setMaintenanceMessage(`Removed ${result.files_removed} unused index files`);
+      await loadStats();
+    } catch (error) {
+      console.error('Failed to clean up index:', error);
+      alert('Failed to clean up index: ' + error);
+    }
+  };
+
+  const handleVerify = async () => {
+    try {
+      const result = await invoke<IndexVerification>('verify_index');
+      setMaintenanceMessage(result.is_healthy
+        ? `Index is healthy (${result.documents_checked} items checked)`
+        : `${result.duplicate_path_count} duplicate paths, ${result.outside_root_count} files outside indexed folders. Reindex to fix.`);
+    } catch (error) {
+      console.error('Failed to verify index:', error);
+      alert('Failed to verify index: ' + error);
+    }
+  };
+
+  const handleExportSnapshot = async () => {
+    if (!snapshotPath.trim()) return;
+    try {
+      const manifest = await invoke<{ document_count: number }>('export_snapshot', { path: snapshotPath });
+      setMaintenanceMessage(`Exported ${manifest.document_count} items to ${snapshotPath}`);
+    } catch (error) {
+      console.error('Failed to export snapshot:', error);
+      alert('Failed to export snapshot: ' + error);
+    }
+  };
+
+  const handleImportSnapshot = async () => {
+    if (!snapshotPath.trim()) return;
+    try {
+      // Reconciliation with local files continues as a background job
+      await invoke<number>('import_snapshot', {
+        path: snapshotPath,
+        rebaseFrom: rebaseFrom || null,
+        rebaseTo: rebaseTo || null,
+      });
+      await loadConfig();
+      await loadStats();
+    } catch (error) {
+      console.error('Failed to import snapshot:', error);
+      alert('Failed to import snapshot: ' + error);
+    }
+  };
+
+  const handleFindDuplicates = async () => {
+    try {
+      const groups = await invoke<DuplicateGroup[]>('find_duplicates');
+      setDuplicates(groups);
+    } catch (error) {
+      console.error('Failed to find duplicates:', error);
+      alert('Failed to find duplicates: ' + error);
+    }
+  };
+
+  const activeJobs = jobs.filter((job) => !isJobFinished(job));
+  const lastFinishedJob = [...jobs].reverse().find((job) => job.state === 'completed');
+  const isReindexing = activeJobs.some((job) => job.kind === 'reindex');
+
   return (
     <div className="settings-panel">
+      <h3>Workspace</h3>
+      <p style={{ color: 'var(--text-secondary)', fontSize: '14px', marginBottom: '12px' }}>
+        Each workspace has its own folders, exclusions, browser settings and index. Search with <code>ws:all</code> to search every workspace.
+      </p>
+      <div className="folder-list">
+        {workspaces.map((workspace) => (
+          <div key={workspace.name} className="folder-item">
+            <span className="folder-path">
+              {workspace.name} ({workspace.indexed_folders.length} folders)
+            </span>
+            {workspace.name === config.active_workspace ? (
+              <span style={{ color: 'var(--text-secondary)', fontSize: '12px' }}>Active</span>
+            ) : (
+              <div style={{ display: 'flex', gap: '8px' }}>
+                <button className="btn btn-primary" onClick={() => handleSwitchWorkspace(workspace.name)}>
+                  Switch
+                </button>
+                <button className="btn btn-danger" onClick={() => handleDeleteWorkspace(workspace.name)}>
+                  Delete
+                </button>
+              </div>
+            )}
+          </div>
+        ))}
+      </div>
+      <div style={{ display: 'flex', gap: '8px', marginTop: '12px', marginBottom: '24px' }}>
+        <input
+          type="text"
+          value={newWorkspace}
+          onChange={(e) => setNewWorkspace(e.target.value)}
+          placeholder="New workspace name"
+          style={{
+            flex: 1,
+            padding: '8px 12px',
+            borderRadius: '6px',
+            border: '1px solid var(--border)',
+            background: 'var(--bg-primary)',
+            color: 'var(--text-primary)',
+          }}
+        />
+        <button className="btn btn-primary" onClick={handleCreateWorkspace}>
+          Create
+        </button>
+      </div>
+
       <h3>Indexed Folders</h3>
       <div style={{ display: 'flex', gap: '8px', marginTop: '12px' }}>
         <input
@@ -178,6 +541,43 @@ function Settings() {
         )}
       </div>
 
+      <h3 style={{ marginTop: '24px' }}>Excluded Folders</h3>
+      <p style={{ color: 'var(--text-secondary)', fontSize: '14px', marginBottom: '12px' }}>
+        Folder names like <code>node_modules</code> are skipped everywhere; full paths skip just that folder.
+      </p>
+      <div style={{ display: 'flex', gap: '8px' }}>
+        <input
+          type="text"
+          value={newExclusion}
+          onChange={(e) => setNewExclusion(e.target.value)}
+          placeholder="node_modules or C:\\path\\to\\folder"
+          style={{
+            flex: 1,
+            padding: '8px 12px',
+            borderRadius: '6px',
+            border: '1px solid var(--border)',
+            background: 'var(--bg-primary)',
+            color: 'var(--text-primary)',
+          }}
+        />
+        <button className="btn btn-primary" onClick={handleAddExclusion}>
+          Exclude
+        </button>
+      </div>
+      <div className="folder-list">
+        {config.excluded_folders.map((folder) => (
+          <div key={folder} className="folder-item">
+            <span className="folder-path">{folder}</span>
+            <button
+              className="btn btn-danger"
+              onClick={() => saveExclusions(config.excluded_folders.filter((f) => f !== folder))}
+            >
+              Remove
+            </button>
+          </div>
+        ))}
+      </div>
+
       <div style={{ marginTop: '24px' }}>
         <div className="stats-container" style={{
           background: 'var(--bg-secondary)',
@@ -213,6 +613,59 @@ function Settings() {
             </div>
           </div>
 
+          <div style={{ display: 'grid', gridTemplateColumns: 'repeat(2, 1fr)', gap: '8px', marginTop: '12px' }}>
+            <div>
+              <div style={{ color: 'var(--text-secondary)', fontSize: '11px' }}>Segments</div>
+              <div style={{ fontWeight: 'bold' }}>{stats?.segment_count ?? '-'}</div>
+            </div>
+            <div>
+              <div style={{ color: 'var(--text-secondary)', fontSize: '11px' }}>Deleted (pending merge)</div>
+              <div style={{ fontWeight: 'bold' }}>{stats?.deleted_doc_count ?? '-'}</div>
+            </div>
+          </div>
+
+          <div style={{ display: 'grid', gridTemplateColumns: 'repeat(2, 1fr)', gap: '8px', marginTop: '12px' }}>
+            <div>
+              <div style={{ color: 'var(--text-secondary)', fontSize: '11px' }}>Last Crawl Started</div>
+              <div style={{ fontWeight: 'bold' }}>{formatTimestamp(stats?.last_crawl_started)}</div>
+            </div>
+            <div>
+              <div style={{ color: 'var(--text-secondary)', fontSize: '11px' }}>Last Indexed</div>
+              <div style={{ fontWeight: 'bold' }}>{formatTimestamp(stats?.last_indexed)}</div>
+            </div>
+            <div>
+              <div style={{ color: 'var(--text-secondary)', fontSize: '11px' }}>File Watcher</div>
+              <div style={{ fontWeight: 'bold' }}>
+                {stats ? (stats.watcher_running ? `Watching ${stats.watched_folders.length} folder(s)` : 'Stopped') : '-'}
+              </div>
+            </div>
+            <div>
+              <div style={{ color: 'var(--text-secondary)', fontSize: '11px' }}>Failed to Index</div>
+              <div style={{ fontWeight: 'bold' }}>{stats?.failed_file_count ?? '-'}</div>
+            </div>
+          </div>
+
+          {stats && stats.folders.length > 0 && (
+            <div style={{ marginTop: '12px', borderTop: '1px solid var(--border)', paddingTop: '12px', fontSize: '12px' }}>
+              {stats.folders.map((folder) => (
+                <div key={folder.path} style={{ display: 'flex', justifyContent: 'space-between', gap: '8px' }}>
+                  <span style={{ overflow: 'hidden', textOverflow: 'ellipsis', whiteSpace: 'nowrap' }}>{folder.path}</span>
+                  <span style={{ color: 'var(--text-secondary)', whiteSpace: 'nowrap' }}>
+                    {folder.document_count} files · {formatBytes(folder.size_bytes)}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
+
+          {stats && stats.top_extensions.length > 0 && (
+            <div style={{ marginTop: '8px', fontSize: '12px', color: 'var(--text-secondary)' }}>
+              {stats.top_extensions
+                .map((e) => `${e.extension ? '.' + e.extension : '(none)'} ${e.document_count}`)
+                .join(' · ')}
+            </div>
+          )}
+
           {stats?.index_path && (
             <div style={{ marginTop: '12px', fontSize: '11px', color: 'var(--text-secondary)', wordBreak: 'break-all' }}>
               Location: {stats.index_path}
@@ -220,6 +673,53 @@ function Settings() {
           )}
         </div>
 
+        {activeJobs.map((job) => {
+          const percent = job.files_total > 0 ? Math.round((job.files_seen / job.files_total) * 100) : 0;
+          return (
+            <div key={job.id} className="index-job" style={{
+              background: 'var(--bg-secondary)',
+              padding: '12px 16px',
+              borderRadius: '8px',
+              marginBottom: '12px'
+            }}>
+              <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center', marginBottom: '8px' }}>
+                <div style={{ fontWeight: 'bold', fontSize: '14px' }}>
+                  {JOB_LABELS[job.kind]}
+                  {job.state === 'paused' && ' (paused)'}
+                  {job.state === 'queued' && ' (queued)'}
+                </div>
+                <div style={{ display: 'flex', gap: '8px' }}>
+                  {job.state === 'paused' ? (
+                    <button className="btn btn-primary" onClick={() => controlJob('resume_index_job', job.id)}>Resume</button>
+                  ) : (
+                    <button className="btn btn-primary" onClick={() => controlJob('pause_index_job', job.id)}>Pause</button>
+                  )}
+                  <button className="btn btn-danger" onClick={() => controlJob('cancel_index_job', job.id)}>Cancel</button>
+                </div>
+              </div>
+              <div style={{ height: '6px', borderRadius: '3px', background: 'var(--border)', overflow: 'hidden' }}>
+                <div style={{ width: `${percent}%`, height: '100%', background: 'var(--accent)', transition: 'width .2s' }} />
+              </div>
+              <div style={{ marginTop: '6px', fontSize: '12px', color: 'var(--text-secondary)' }}>
+                {job.state === 'scanning'
+                  ? 'Looking for files...'
+                  : `${job.files_seen} / ${job.files_total} files checked · ${formatJobChanges(job)}`}
+              </div>
+              {job.current_path && (
+                <div style={{ fontSize: '11px', color: 'var(--text-secondary)', wordBreak: 'break-all' }}>
+                  {job.current_path}
+                </div>
+              )}
+            </div>
+          );
+        })}
+
+        {activeJobs.length === 0 && lastFinishedJob && (
+          <div style={{ fontSize: '12px', color: 'var(--text-secondary)', marginBottom: '12px' }}>
+            Last update ({JOB_LABELS[lastFinishedJob.kind].toLowerCase()}): {formatJobChanges(lastFinishedJob)}
+          </div>
+        )}
+
         <button
           className="btn btn-primary"
           onClick={handleReindex}
@@ -227,6 +727,212 @@ function Settings() {
         >
           {isReindexing ? 'Reindexing...' : 'Reindex All Files'}
         </button>
+
+        <div style={{ display: 'flex', gap: '8px', marginTop: '12px' }}>
+          <button className="btn btn-primary" onClick={handleMerge}>Compact Index</button>
+          <button className="btn btn-primary" onClick={handleGarbageCollect}>Clean Up Files</button>
+          <button className="btn btn-primary" onClick={handleVerify}>Verify Index</button>
+        </div>
+        {maintenanceMessage && (
+          <div style={{ fontSize: '12px', color: 'var(--text-secondary)', marginTop: '8px' }}>
+            {maintenanceMessage}
+          </div>
+        )}
+      </div>
+
+      <div style={{ marginTop: '24px' }}>
+        <h3 style={{ marginBottom: '12px' }}>Shared Indexes</h3>
+        <p style={{ color: 'var(--text-secondary)', fontSize: '14px', marginBottom: '12px' }}>
+          Prebuilt indexes (e.g. of a team documentation share) searched read-only together with your own. Results are labelled with the index they came from.
+        </p>
+        <div style={{ display: 'flex', gap: '8px' }}>
+          <input
+            type="text"
+            value={sharedLabel}
+            onChange={(e) => setSharedLabel(e.target.value)}
+            placeholder="Label, e.g. Team Docs"
+            style={{
+              width: '160px',
+              padding: '8px 12px',
+              borderRadius: '6px',
+              border: '1px solid var(--border)',
+              background: 'var(--bg-primary)',
+              color: 'var(--text-primary)',
+            }}
+          />
+          <input
+            type="text"
+            value={sharedPath}
+            onChange={(e) => setSharedPath(e.target.value)}
+            placeholder="\\\\server\\share\\worksentry\\index"
+            style={{
+              flex: 1,
+              padding: '8px 12px',
+              borderRadius: '6px',
+              border: '1px solid var(--border)',
+              background: 'var(--bg-primary)',
+              color: 'var(--text-primary)',
+            }}
+          />
+          <button className="btn btn-primary" onClick={handleAddSharedIndex}>Add</button>
+        </div>
+        <div className="folder-list">
+          {config.shared_indexes.map((shared) => (
+            <div key={shared.label} className="folder-item">
+              <span className="folder-path">{shared.label}: {shared.path}</span>
+              <button className="btn btn-danger" onClick={() => handleRemoveSharedIndex(shared.label)}>
+                Remove
+              </button>
+            </div>
+          ))}
+        </div>
+      </div>
+
+      <div style={{ marginTop: '24px' }}>
+        <h3 style={{ marginBottom: '12px' }}>Synonyms</h3>
+        <p style={{ color: 'var(--text-secondary)', fontSize: '14px', marginBottom: '12px' }}>
+          Words that mean the same thing to you. Searching for one also finds the others, ranked slightly below direct matches.
+        </p>
+        <div style={{ display: 'flex', gap: '8px' }}>
+          <input
+            type="text"
+            value={newSynonyms}
+            onChange={(e) => setNewSynonyms(e.target.value)}
+            placeholder="Comma-separated, e.g. k8s, kubernetes"
+            style={{
+              flex: 1,
+              padding: '8px 12px',
+              borderRadius: '6px',
+              border: '1px solid var(--border)',
+              background: 'var(--bg-primary)',
+              color: 'var(--text-primary)',
+            }}
+          />
+          <button className="btn btn-primary" onClick={handleAddSynonyms}>Add</button>
+        </div>
+        <div className="folder-list">
+          {config.synonyms.map((group, i) => (
+            <div key={group.join(',')} className="folder-item">
+              <span className="folder-path">{group.join(' ⇄ ')}</span>
+              <button
+                className="btn btn-danger"
+                onClick={() => saveSynonyms(config.synonyms.filter((_, j) => j !== i))}
+              >
+                Remove
+              </button>
+            </div>
+          ))}
+        </div>
+      </div>
+
+      <div style={{ marginTop: '24px' }}>
+        <h3 style={{ marginBottom: '12px' }}>Editor</h3>
+        <p style={{ color: 'var(--text-secondary)', fontSize: '14px', marginBottom: '12px' }}>
+          Command that opens a file at a line, used for symbol results and matching lines. <code>{'{path}'}</code> and <code>{'{line}'}</code> are filled in.
+        </p>
+        <div style={{ display: 'flex', gap: '8px' }}>
+          <input
+            type="text"
+            value={config.editor_command ?? ''}
+            onChange={(e) => setConfig(prev => ({ ...prev, editor_command: e.target.value }))}
+            placeholder="code --goto {path}:{line}"
+            style={{
+              flex: 1,
+              padding: '8px 12px',
+              borderRadius: '6px',
+              border: '1px solid var(--border)',
+              background: 'var(--bg-primary)',
+              color: 'var(--text-primary)',
+            }}
+          />
+          <button className="btn btn-primary" onClick={handleSaveEditorCommand}>Save</button>
+        </div>
+      </div>
+
+      <div style={{ marginTop: '24px' }}>
+        <h3 style={{ marginBottom: '12px' }}>Index Snapshot</h3>
+        <p style={{ color: 'var(--text-secondary)', fontSize: '14px', marginBottom: '12px' }}>
+          Move the index to another machine instead of rebuilding it. When importing, paths under the old root are moved to the new root.
+        </p>
+        <div style={{ display: 'flex', gap: '8px' }}>
+          <input
+            type="text"
+            value={snapshotPath}
+            onChange={(e) => setSnapshotPath(e.target.value)}
+            placeholder="C:\\path\\to\\worksentry-index.tar"
+            style={{
+              flex: 1,
+              padding: '8px 12px',
+              borderRadius: '6px',
+              border: '1px solid var(--border)',
+              background: 'var(--bg-primary)',
+              color: 'var(--text-primary)',
+            }}
+          />
+          <button className="btn btn-primary" onClick={handleExportSnapshot}>Export</button>
+          <button className="btn btn-primary" onClick={handleImportSnapshot}>Import</button>
+        </div>
+        <div style={{ display: 'flex', gap: '8px', marginTop: '8px' }}>
+          <input
+            type="text"
+            value={rebaseFrom}
+            onChange={(e) => setRebaseFrom(e.target.value)}
+            placeholder="Old root (optional), e.g. D:\\Data"
+            style={{
+              flex: 1,
+              padding: '8px 12px',
+              borderRadius: '6px',
+              border: '1px solid var(--border)',
+              background: 'var(--bg-primary)',
+              color: 'var(--text-primary)',
+            }}
+          />
+          <input
+            type="text"
+            value={rebaseTo}
+            onChange={(e) => setRebaseTo(e.target.value)}
+            placeholder="New root, e.g. /mnt/data"
+            style={{
+              flex: 1,
+              padding: '8px 12px',
+              borderRadius: '6px',
+              border: '1px solid var(--border)',
+              background: 'var(--bg-primary)',
+              color: 'var(--text-primary)',
+            }}
+          />
+        </div>
+      </div>
+
+      <div style={{ marginTop: '24px' }}>
+        <h3 style={{ marginBottom: '12px' }}>Duplicate Files</h3>
+        <p style={{ color: 'var(--text-secondary)', fontSize: '14px', marginBottom: '12px' }}>
+          Files with identical content across all indexed folders. Search with <code>dup:</code> to filter results the same way.
+        </p>
+        <button className="btn btn-primary" onClick={handleFindDuplicates}>
+          Find Duplicates
+        </button>
+
+        {duplicates && duplicates.length === 0 && (
+          <p style={{ color: 'var(--text-secondary)', marginTop: '12px' }}>No duplicates found</p>
+        )}
+        {duplicates && duplicates.map((group) => (
+          <div key={group.hash + group.size} style={{
+            background: 'var(--bg-secondary)',
+            padding: '12px 16px',
+            borderRadius: '8px',
+            marginTop: '12px'
+          }}>
+            <div style={{ fontWeight: 'bold', fontSize: '13px', marginBottom: '6px' }}>
+              {group.paths.length} copies · {formatBytes(group.size)} each
+            </div>
+            {group.paths.map((path) => (
+              <div key={path} style={{ fontSize: '12px', color: 'var(--text-secondary)', wordBreak: 'break-all' }}>
+                {path}
+              </div>
+            ))}
+          </div>
+        ))}
       </div>
 
       <div style={{ marginTop: '24px' }}>
@@ -3,11 +3,19 @@ import { render, screen, fireEvent, waitFor } from '@testing-library/react';
 import App from '../../src/App';
 
 const mockInvoke = vi.fn();
+let emitSearchEvent: (payload: unknown) => void = () => {};
 
 vi.mock('@tauri-apps/api/core', () => ({
     invoke: mockInvoke,
 }));
 
+vi.mock('@tauri-apps/api/event', () => ({
+    listen: vi.fn((_event: string, handler: (event: { payload: unknown }) => void) => {
+        emitSearchEvent = (payload) => handler({ payload });
+        return Promise.resolve(() => {});
+    }),
+}));
+
 describe('App', () => {
     beforeEach(() => {
         vi.clearAllMocks();
@@ -38,11 +46,12 @@ describe('App', () => {
     });
 
     it('displays empty state when no results', async () => {
-        mockInvoke.mockResolvedValue([]);
+        mockInvoke.mockResolvedValue(1);
 
         render(<App />);
         const input = screen.getByPlaceholderText('Search files...');
         fireEvent.change(input, { target: { value: 'test' } });
+        emitSearchEvent({ search_id: 1, phase: 'filename', results: [], done: true });
 
         await waitFor(() => {
             expect(screen.getByText('No results found')).toBeInTheDocument();
@@ -60,18 +69,105 @@ describe('App', () => {
     });
 
     it('displays search results', async () => {
-        mockInvoke.mockResolvedValue([
-            { path: '/test/file1.txt', file_name: 'file1.txt', score: 1.0 },
-            { path: '/test/file2.txt', file_name: 'file2.txt', score: 0.9 },
-        ]);
+        mockInvoke.mockResolvedValue(1);
 
         render(<App />);
         const input = screen.getByPlaceholderText('Search files...');
         fireEvent.change(input, { target: { value: 'test' } });
+        emitSearchEvent({
+            search_id: 1,
+            phase: 'filename',
+            results: [{ path: '/test/file1.txt', file_name: 'file1.txt', score: 1.0 }],
+            done: false,
+        });
+        emitSearchEvent({
+            search_id: 1,
+            phase: 'content',
+            results: [{ path: '/test/file2.txt', file_name: 'file2.txt', score: 0.9 }],
+            done: true,
+        });
 
         await waitFor(() => {
             expect(screen.getByText('file1.txt')).toBeInTheDocument();
             expect(screen.getByText('file2.txt')).toBeInTheDocument();
         });
+        expect(mockInvoke).toHaveBeenCalledWith('start_search', { query: 'test', limit: 20 });
+    });
+
+    it('offers spelling suggestions when nothing was found', async () => {
+        mockInvoke.mockResolvedValue(1);
+
+        render(<App />);
+        const input = screen.getByPlaceholderText('Search files...');
+        fireEvent.change(input, { target: { value: 'progrmaing' } });
+        emitSearchEvent({ search_id: 1, phase: 'filename', results: [], done: false });
+        emitSearchEvent({ search_id: 1, phase: 'content', results: [], done: true, suggestions: ['programming'] });
+
+        fireEvent.click(await screen.findByText('programming'));
+        expect(mockInvoke).toHaveBeenCalledWith('start_search', { query: 'programming', limit: 20 });
+    });
+
+    it('ignores events from superseded searches', async () => {
+        mockInvoke.mockResolvedValue(2);
+
+        render(<App />);
+        const input = screen.getByPlaceholderText('Search files...');
+        fireEvent.change(input, { target: { value: 'test' } });
+        emitSearchEvent({
+            search_id: 2,
+            phase: 'filename',
+            results: [{ path: '/test/new.txt', file_name: 'new.txt', score: 1.0 }],
+            done: true,
+        });
+        emitSearchEvent({
+            search_id: 1,
+            phase: 'filename',
+            results: [{ path: '/test/old.txt', file_name: 'old.txt', score: 1.0 }],
+            done: true,
+        });
+
+        await waitFor(() => {
+            expect(screen.getByText('new.txt')).toBeInTheDocument();
+        });
+        expect(screen.queryByText('old.txt')).not.toBeInTheDocument();
+    });
+
+    it('shows the next search after clearing the query repeatedly', async () => {
+        let nextSearchId = 1;
+        mockInvoke.mockImplementation((command: string) =>
+            Promise.resolve(command === 'start_search' ? nextSearchId++ : []));
+
+        render(<App />);
+        const input = screen.getByPlaceholderText('Search files...');
+        fireEvent.change(input, { target: { value: 'old' } });
+        await waitFor(() => expect(nextSearchId).toBe(2));
+        fireEvent.change(input, { target: { value: '' } });
+        fireEvent.change(input, { target: { value: ' ' } });
+        fireEvent.change(input, { target: { value: 'new' } });
+        await waitFor(() => expect(nextSearchId).toBe(3));
+        emitSearchEvent({ search_id: 1, phase: 'filename', results: [{ path: '/old.txt', file_name: 'old.txt', score: 1.0 }], done: true });
+        emitSearchEvent({ search_id: 2, phase: 'filename', results: [{ path: '/new.txt', file_name: 'new.txt', score: 1.0 }], done: true });
+
+        expect(await screen.findByText('new.txt')).toBeInTheDocument();
+        expect(screen.queryByText('old.txt')).not.toBeInTheDocument();
+    });
+
+    it('ignores completions that arrive after newer ones', async () => {
+        const pending: Record<string, (found: unknown) => void> = {};
+        mockInvoke.mockImplementation((command: string, args: { prefix?: string }) => {
+            if (command !== 'suggest') return Promise.resolve(1);
+            return new Promise((resolve) => { pending[args.prefix ?? ''] = resolve; });
+        });
+
+        render(<App />);
+        const input = screen.getByPlaceholderText('Search files...');
+        fireEvent.change(input, { target: { value: 're' } });
+        fireEvent.change(input, { target: { value: 'rep' } });
+        pending['rep']([{ text: 'report', source: 'filename' }]);
+        expect(await screen.findByText('report')).toBeInTheDocument();
+        pending['re']([{ text: 'readme', source: 'filename' }]);
+
+        await waitFor(() => expect(screen.getByText('report')).toBeInTheDocument());
+        expect(screen.queryByText('readme')).not.toBeInTheDocument();
     });
 });
@@ -53,4 +53,46 @@ describe('ResultsList', () => {
 
         expect(screen.getByText('/test/file1.txt')).toBeInTheDocument();
     });
+
+    it('opens content hits at the clicked line', () => {
+        const onSelect = vi.fn();
+        const hit = {
+            path: '/test/notes.txt',
+            file_name: 'notes.txt',
+            score: 1.0,
+            line_matches: [
+                { line: 3, text: 'first report', match_positions: [6, 7, 8, 9, 10, 11] },
+                { line: 9, text: 'second report', match_positions: [7, 8, 9, 10, 11, 12] },
+            ],
+        };
+        const { container } = render(<ResultsList results={[hit]} selectedIndex={0} isLoading={false} onSelect={onSelect} />);
+
+        const lines = container.querySelectorAll('.result-line');
+        expect(lines).toHaveLength(2);
+        expect(lines[1].querySelectorAll('b')).toHaveLength(6);
+
+        fireEvent.click(lines[1]);
+        expect(onSelect).toHaveBeenCalledTimes(1);
+        expect(onSelect).toHaveBeenCalledWith({ ...hit, line: 9 });
+    });
+
+    it('offers facet counts as refinements', () => {
+        const onRefine = vi.fn();
+        const facets = {
+            record_types: [
+                { value: 'file', count: 34, filter: 'in:files' },
+                { value: 'Bookmark', count: 12, filter: 'in:bookmarks' },
+            ],
+            extensions: [{ value: 'pdf', count: 34, filter: 'ext:pdf' }],
+            types: [{ value: 'doc', count: 34, filter: 'type:doc' }],
+            folders: [{ value: '/home/me/My Files', count: 3, filter: null }],
+        };
+        render(<ResultsList results={mockResults} selectedIndex={0} isLoading={false} onSelect={vi.fn()} facets={facets} onRefine={onRefine} />);
+
+        expect(screen.getByText('Bookmarks (12)')).toBeInTheDocument();
+        expect(screen.queryByText('My Files (3)')).not.toBeInTheDocument();
+
+        fireEvent.click(screen.getByText('PDF (34)'));
+        expect(onRefine).toHaveBeenCalledWith('ext:pdf');
+    });
 });
@@ -45,4 +45,18 @@ describe('SearchBar', () => {
         const input = screen.getByPlaceholderText('Search files...');
         expect(input).toHaveValue('hello world');
     });
+
+    it('shows completions with their source and accepts one on click', () => {
+        const onChange = vi.fn();
+        const completions = [
+            { text: 'report 2024', source: 'recent_query' as const },
+            { text: 'revenue', source: 'content' as const },
+        ];
+
+        render(<SearchBar query="re" onChange={onChange} onKeyDown={vi.fn()} completions={completions} />);
+
+        expect(screen.getByText('recent')).toBeInTheDocument();
+        fireEvent.click(screen.getByText('revenue'));
+        expect(onChange).toHaveBeenCalledWith('revenue');
+    });
 });